@@ -0,0 +1,71 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Context;
+
+use crate::days;
+
+const ANSWERS_FILE_NAME: &str = "answers.txt";
+
+/// Recorded answers for a single input profile.
+///
+/// Each profile keeps its own store next to its inputs, as `answers.txt`.
+/// Every line in that file holds one answer, in the form `<DAY> <PART> <ANSWER>`.
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+impl AnswerStore {
+    /// Load the answers store for the given `profile`, which is empty if it doesn't exist yet.
+    pub fn load(profile: Option<&str>) -> anyhow::Result<Self> {
+        let mut path = days::inputs_dir(profile);
+        path.push(ANSWERS_FILE_NAME);
+
+        let mut answers = BTreeMap::new();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).context(format!("Could not read {}", path.display())),
+        };
+        for (idx, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.splitn(3, ' ');
+            let mut next_field = || {
+                fields.next().with_context(|| {
+                    format!("Malformed answer on line {} of {}", idx + 1, path.display())
+                })
+            };
+            let day = next_field()?.parse().context("Answer day should be a u8")?;
+            let part = next_field()?
+                .parse()
+                .context("Answer part should be a u8")?;
+            let answer = next_field()?.to_string();
+            answers.insert((day, part), answer);
+        }
+
+        Ok(Self { path, answers })
+    }
+
+    /// Get the recorded answer for a given `day` and `part`, if there is one.
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    /// Record an answer, replacing any previous one for the same `day` and `part`.
+    pub fn record(&mut self, day: u8, part: u8, answer: String) {
+        self.answers.insert((day, part), answer);
+    }
+
+    /// Write the store back to disk.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let contents: String = self
+            .answers
+            .iter()
+            .map(|((day, part), answer)| format!("{day} {part} {answer}\n"))
+            .collect();
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("Could not write {}", self.path.display()))
+    }
+}
@@ -1,13 +1,317 @@
-use clap::Parser;
+use std::{ffi::OsString, path::PathBuf};
+
+use clap::{
+    Args, CommandFactory, FromArgMatches, Parser, Subcommand, error::ErrorKind, parser::ValueSource,
+};
+
+use crate::{
+    days::{Counting, Wheel},
+    puzzle::DEFAULT_IMPL,
+    trace::TraceFormat,
+};
 
 /// CLI runner for my solutions to AoC 2025, written in Rust.
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    subcommand_negates_reqs = true,
+    override_usage = "aoc_2025 [OPTIONS] <DAY> <PART>\n       aoc_2025 [OPTIONS] <COMMAND>"
+)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub run: RunArgs,
+    /// Named input profile; inputs are read from `inputs/<PROFILE>/` instead of `inputs/`
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// Read the input from this file instead, which may be gzip or zstd compressed
+    #[arg(long, global = true)]
+    pub input: Option<PathBuf>,
+    /// Reject anything unexpected in the input with an error (the default)
+    #[arg(long, global = true, conflicts_with = "lenient")]
+    pub strict: bool,
+    /// Skip blank and `#` comment lines in the input and trim leading whitespace, reporting what
+    /// was changed
+    #[arg(long, global = true)]
+    pub lenient: bool,
+}
+
+impl Cli {
+    /// Parse the command line, exiting with a usage error if it isn't valid.
+    pub fn parse_args() -> Self {
+        Self::try_parse_args_from(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse the arguments `args`, where the global options may come before or after the
+    /// subcommand.
+    ///
+    /// The [`RunArgs`] only make sense for the default run, and some subcommands have no use for
+    /// some of the global options, so those are rejected here rather than silently ignored. Clap
+    /// could reject the former itself, but would then stop looking for a subcommand once it has
+    /// seen any option.
+    pub fn try_parse_args_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut cmd = Self::command();
+        let matches = cmd.try_get_matches_from_mut(args)?;
+        let cli = Self::from_arg_matches(&matches)?;
+
+        if let (Some(command), Some(name)) = (&cli.command, matches.subcommand_name()) {
+            let disallowed = [
+                (&RUN_ARGS[..], "a subcommand".to_string()),
+                (command.unused_options(), format!("the '{name}' subcommand")),
+            ];
+            for (ids, with) in disallowed {
+                if let Some(arg) = cmd.get_arguments().find(|arg| {
+                    let id = arg.get_id().as_str();
+                    ids.contains(&id) && matches.value_source(id) == Some(ValueSource::CommandLine)
+                }) {
+                    let message = format!("the argument '{arg}' cannot be used with {with}");
+                    return Err(cmd.clone().error(ErrorKind::ArgumentConflict, message));
+                }
+            }
+        }
+        Ok(cli)
+    }
+}
+
+/// IDs of the [`RunArgs`], which can't be combined with a subcommand.
+const RUN_ARGS: [&str; 4] = ["day", "part", "save", "implementation"];
+
+/// Arguments for the default action of running a single part of a day.
+#[derive(Args)]
+pub struct RunArgs {
     /// Which day you want to run
-    #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
-    pub day: u8,
+    #[arg(required = true, value_parser = clap::value_parser!(u8).range(1..=12))]
+    pub day: Option<u8>,
     /// Which part you want to run
-    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
-    pub part: u8,
+    #[arg(required = true, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+    /// Record the answer in the profile's answers store
+    #[arg(long)]
+    pub save: bool,
+    /// Which of the part's implementations to run, see `crosscheck` for every one of them
+    #[arg(long = "impl", value_name = "NAME", default_value = DEFAULT_IMPL)]
+    pub implementation: String,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Turn a combination lock of several day 1 dials by the instructions in day 1's input
+    Lock {
+        /// Size and starting position of a wheel, repeated for each wheel of the lock; an
+        /// instruction like `2:L15` turns the second wheel, and one without a prefix the first
+        #[arg(long = "wheel", value_name = "SIZE@START", default_value = "100@50")]
+        wheels: Vec<Wheel>,
+    },
+    /// Shrink an input on which a part panics, errors or disagrees with another implementation,
+    /// keeping the same failure
+    Minimize {
+        /// Which day's solver fails
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+        /// Which part fails
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Which of the part's implementations fails
+        #[arg(long = "impl", value_name = "NAME", default_value = DEFAULT_IMPL)]
+        implementation: String,
+        /// Another implementation of the part, whose answer the failing one disagrees with
+        #[arg(long, value_name = "NAME")]
+        against: Option<String>,
+        /// File to write the minimized input to, rather than stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Turn day 1's dial by rotations from stdin (or an input file) as they arrive, printing the
+    /// running answers
+    Online {
+        /// Number of rotations between each print of the running answers
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Run both parts of a day against every profile's input and tabulate the answers
+    Profiles {
+        /// Which day you want to run
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+    },
+    /// Run every implementation of a day's parts on the same input, failing if any disagree
+    Crosscheck {
+        /// Which day you want to check
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+        /// Which part you want to check, or both if not given
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+    /// Write a random, valid input for a day, reproducible from its seed
+    Generate {
+        /// Which day you want to generate an input for
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+        /// How large the input should be, ie the number of lines or the side of a grid
+        #[arg(long)]
+        size: usize,
+        /// Seed for the random number generator
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// File to write the input to, rather than stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Count and sum day 2's invalid IDs in ranges beyond the puzzle's own, ie in another radix
+    Ids {
+        /// Which part's definition of an invalid ID to use
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Radix that the range bounds are written in, and whose digits are repeated
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(2..=36))]
+        radix: u32,
+        /// How to count IDs which are in more than one range
+        #[arg(long, value_enum, default_value_t = Counting::Distinct)]
+        counting: Counting,
+        /// List every invalid ID under the range it came from, with each range's subtotal
+        #[arg(long)]
+        report: bool,
+        /// Number of threads to split the ranges across [default: available parallelism]
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        threads: Option<u64>,
+        /// Work through the ranges one after another on a single thread
+        #[arg(long, conflicts_with = "threads")]
+        serial: bool,
+    },
+    /// Print statistics about the shape of a day's input
+    Inspect {
+        /// Which day's input you want to inspect
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+    },
+    /// Estimate how a part's runtime grows by timing it on generated inputs of increasing size
+    Scaling {
+        /// Which day you want to time
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+        /// Which part you want to time
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Size of the smallest generated input
+        #[arg(long, default_value_t = 16)]
+        min_size: usize,
+        /// Number of input sizes to time
+        #[arg(long, default_value_t = 6)]
+        steps: u32,
+        /// Factor by which the size grows at each step, at least 2
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(2..))]
+        factor: u64,
+        /// Seed for the random number generator
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of times each size is timed, keeping the fastest
+        #[arg(long, default_value_t = 3)]
+        repeats: u32,
+    },
+    /// Write a record of every step that a day's solution takes through its input
+    Trace {
+        /// Which day you want to trace
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+        /// Format of the trace
+        #[arg(long, value_enum, default_value_t = TraceFormat::Csv)]
+        format: TraceFormat,
+        /// File to write the trace to, rather than stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Check a day's input against the shape that it expects, without solving it
+    Validate {
+        /// Which day's input you want to check
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=12))]
+        day: u8,
+    },
+}
+
+impl Command {
+    /// IDs of the global options which the command has no use for, so that they're rejected
+    /// rather than ignored.
+    fn unused_options(&self) -> &'static [&'static str] {
+        match self {
+            // Generated inputs aren't read from a file, and are always valid
+            Self::Generate { .. } | Self::Scaling { .. } => {
+                &["profile", "input", "strict", "lenient"]
+            }
+            // Every profile's own input is read
+            Self::Profiles { .. } => &["profile", "input"],
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Cli, clap::Error> {
+        Cli::try_parse_args_from(std::iter::once("aoc_2025").chain(args.split_whitespace()))
+    }
+
+    #[test]
+    fn test_global_options() {
+        for args in ["--profile x validate 1", "validate 1 --profile x"] {
+            let cli = parse(args).unwrap();
+            assert!(matches!(cli.command, Some(Command::Validate { day: 1 })));
+            assert_eq!(Some("x"), cli.profile.as_deref());
+        }
+        for args in ["--lenient profiles 1", "profiles 1 --lenient"] {
+            assert!(parse(args).unwrap().lenient);
+        }
+        for args in ["--input f --lenient ids 1", "ids 1 --input f --lenient"] {
+            let cli = parse(args).unwrap();
+            assert!(matches!(cli.command, Some(Command::Ids { part: 1, .. })));
+            assert_eq!(Some(PathBuf::from("f")), cli.input);
+            assert!(cli.lenient);
+        }
+        for args in ["--input f 2 1", "2 1 --input f"] {
+            let cli = parse(args).unwrap();
+            assert!(cli.command.is_none());
+            assert_eq!((Some(2), Some(1)), (cli.run.day, cli.run.part));
+            assert_eq!(Some(PathBuf::from("f")), cli.input);
+        }
+    }
+
+    #[test]
+    fn test_run_args_with_subcommand() {
+        for args in [
+            "1 validate 1",
+            "--save validate 1",
+            "--impl brute validate 1",
+        ] {
+            let err = parse(args).err().unwrap();
+            assert_eq!(ErrorKind::ArgumentConflict, err.kind(), "{args}");
+        }
+        assert_eq!(
+            ErrorKind::MissingRequiredArgument,
+            parse("--input f 1").err().unwrap().kind()
+        );
+    }
+
+    #[test]
+    fn test_unused_options() {
+        for args in [
+            "--profile x profiles 1",
+            "profiles 1 --input f",
+            "generate 1 --size 5 --input f",
+            "--lenient generate 1 --size 5",
+            "scaling 1 1 --profile x",
+        ] {
+            let err = parse(args).err().unwrap();
+            assert_eq!(ErrorKind::ArgumentConflict, err.kind(), "{args}");
+        }
+        assert!(parse("--strict profiles 1").is_ok());
+    }
 }
@@ -0,0 +1,64 @@
+use std::{
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use itertools::Itertools;
+
+use crate::{days, parse::ParseMode};
+
+/// Run every implementation of `part` of `day` (or of both parts if not given) against the same
+/// input, and fail if any of their answers differ.
+///
+/// An implementation which errors counts as answering with its error.
+pub fn run(
+    day: u8,
+    part: Option<u8>,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let mut raw = vec![];
+    days::get_input(day, profile, input_path)?.read_to_end(&mut raw)?;
+    for warning in days::lint(day, Box::new(Cursor::new(raw.clone())), mode)? {
+        eprintln!("Warning: {warning}");
+    }
+
+    let mut disagreeing_parts = vec![];
+    let mut reported = false;
+    for part in part.map_or(vec![1, 2], |part| vec![part]) {
+        let names = days::select_puzzle(day)?.implementations(part);
+        if names.len() == 1 {
+            eprintln!("Warning: Day {day}, Part {part} only has one implementation");
+        }
+
+        let mut answers = vec![];
+        for name in names {
+            let input = Box::new(Cursor::new(raw.clone()));
+            // Changes made to the input are only worth warning about for the first solve
+            let solved = if reported {
+                days::solve_quietly(day, part, name, input, mode)
+            } else {
+                days::solve_with(day, part, name, input, mode)
+            };
+            reported = true;
+            let ans = match solved {
+                Ok(ans) => ans.to_string(),
+                Err(e) => format!("error: {e:#}"),
+            };
+            println!("Day {day}, Part {part} [{name}]: {ans}");
+            answers.push(ans);
+        }
+        if !answers.iter().all_equal() {
+            disagreeing_parts.push(part);
+        }
+    }
+
+    if !disagreeing_parts.is_empty() {
+        anyhow::bail!(
+            "Implementations disagree on part(s) {}",
+            disagreeing_parts.iter().join(", ")
+        );
+    }
+    Ok(())
+}
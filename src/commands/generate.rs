@@ -0,0 +1,21 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{days, rng::Rng};
+
+/// Write a synthetic input for `day` of the given `size` to `output`, or stdout if not given.
+pub fn run(day: u8, size: usize, seed: u64, output: Option<&Path>) -> anyhow::Result<()> {
+    let puzzle = days::select_puzzle(day)?;
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+
+    (puzzle.generate)(&mut Rng::new(seed), size, &mut out)?;
+    out.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::{
+    days::{self, IdOptions, IdRanges, Invalid, OVERFLOW_MESSAGE},
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+};
+
+/// The day whose invalid IDs are being analysed.
+const DAY: u8 = 2;
+
+/// Count and sum the invalid IDs for `part` of day 2 in the input, read according to the
+/// `options` rather than as the puzzle's own decimal IDs.
+///
+/// With `report`, every invalid ID is listed under the range of the input that it came from,
+/// along with the block that it repeats and each range's subtotal.
+pub fn run(
+    part: u8,
+    options: IdOptions,
+    report: bool,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let input = days::get_input(DAY, profile, input_path)?;
+    let puzzle = days::select_puzzle(DAY)?;
+    let input = NormalizedInput::new(input, puzzle.normalization, mode);
+    let line_map = input.line_map();
+    let report_errors = |e| {
+        let e = parse::remap_lines(e, &line_map);
+        parse::report(e, &days::get_input_path(DAY, profile, input_path))
+    };
+
+    let ranges = IdRanges::read(Box::new(input), options.radix).map_err(report_errors)?;
+    for warning in ranges.overlap_warnings(options) {
+        eprintln!("Warning: {warning}");
+    }
+
+    let invalid = if report {
+        let mut total = Invalid::default();
+        for (idx, range) in days::itemize_invalid_ids(&ranges, part, options)?
+            .iter()
+            .enumerate()
+        {
+            println!(
+                "Range {} ({}-{}): {} invalid IDs, summing to {}",
+                idx + 1,
+                days::to_radix(*range.range.start(), options.radix),
+                days::to_radix(*range.range.end(), options.radix),
+                range.subtotal.count,
+                range.subtotal.sum
+            );
+            for id in &range.ids {
+                match id.counted_in {
+                    Some(earlier) => println!(
+                        "  {}, already counted in range {}",
+                        id.describe(options.radix),
+                        earlier + 1
+                    ),
+                    None => println!("  {}", id.describe(options.radix)),
+                }
+            }
+            total = total
+                .checked_add(&range.subtotal)
+                .context(OVERFLOW_MESSAGE)?;
+        }
+        total
+    } else {
+        days::find_invalid_ids(&ranges, part, options)?
+    };
+
+    println!(
+        "Day {DAY}, Part {part} in base {}: {} invalid IDs, summing to {}",
+        options.radix, invalid.count, invalid.sum
+    );
+    Ok(())
+}
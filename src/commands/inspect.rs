@@ -0,0 +1,69 @@
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use crate::{
+    days,
+    inspect::{Stat, percent, summarize},
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+};
+
+/// Print generic statistics about the raw input for `day`, followed by the day's own summary
+/// of its (normalized) input.
+pub fn run(
+    day: u8,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let mut raw = vec![];
+    days::get_input(day, profile, input_path)?.read_to_end(&mut raw)?;
+    let puzzle = days::select_puzzle(day)?;
+
+    let text = String::from_utf8_lossy(&raw);
+    let line_lengths = text.lines().map(|line| line.chars().count());
+    print_stats(
+        &format!("Input for Day {day}"),
+        vec![
+            Stat::new("Bytes", raw.len()),
+            Stat::new("Lines", text.lines().count()),
+            Stat::new("Line length", summarize(line_lengths)),
+        ],
+    );
+
+    let mut histogram = BTreeMap::new();
+    for c in text.chars() {
+        *histogram.entry(c).or_insert(0) += 1;
+    }
+    let char_ct = histogram.values().sum();
+    let histogram = histogram
+        .into_iter()
+        .map(|(c, ct)| Stat::new(format!("{c:?}"), format!("{ct} ({})", percent(ct, char_ct))))
+        .collect();
+    print_stats("Characters", histogram);
+
+    let input = NormalizedInput::new(Cursor::new(raw), puzzle.normalization, mode);
+    let line_map = input.line_map();
+    let stats = (puzzle.inspect)(Box::new(input))
+        .map_err(|e| parse::remap_lines(e, &line_map))
+        .map_err(|e| parse::report(e, &days::get_input_path(day, profile, input_path)))?;
+    print_stats(&format!("Day {day} specifics"), stats);
+
+    Ok(())
+}
+
+/// Print a section of statistics under a `title`, with their values aligned.
+pub fn print_stats(title: &str, stats: Vec<Stat>) {
+    let width = stats
+        .iter()
+        .map(|stat| stat.name.len())
+        .max()
+        .unwrap_or_default();
+    println!("{title}:");
+    for Stat { name, value } in stats {
+        println!("  {name:<width$}  {value}");
+    }
+}
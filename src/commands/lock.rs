@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use crate::{
+    commands::inspect::print_stats,
+    days::{self, Wheel},
+    inspect::Stat,
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+};
+
+/// The day whose dial each wheel of the lock is modelled on, and whose input it reads.
+const DAY: u8 = 1;
+
+/// Turn a combination lock with the given `wheels` by the instructions in the input, printing
+/// how often each wheel, and the whole lock, pointed at zero.
+pub fn run(
+    wheels: &[Wheel],
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let input = days::get_input(DAY, profile, input_path)?;
+    let puzzle = days::select_puzzle(DAY)?;
+    let input = NormalizedInput::new(input, puzzle.normalization, mode);
+    let line_map = input.line_map();
+    let report = days::simulate_lock(input, wheels)
+        .map_err(|e| parse::remap_lines(e, &line_map))
+        .map_err(|e| parse::report(e, &days::get_input_path(DAY, profile, input_path)))?;
+
+    for (idx, wheel) in report.wheels.iter().enumerate() {
+        print_stats(
+            &format!("Wheel {} ({})", idx + 1, wheel.wheel),
+            vec![
+                Stat::new("Rotations", wheel.rotations),
+                Stat::new("Landed on zero", wheel.landed),
+                Stat::new("Passed zero", wheel.passed),
+            ],
+        );
+    }
+    let landed: usize = report.wheels.iter().map(|wheel| wheel.landed).sum();
+    let passed = report
+        .wheels
+        .iter()
+        .try_fold(0u128, |sum, wheel| sum.checked_add(wheel.passed));
+    print_stats(
+        "Whole lock",
+        vec![
+            Stat::new("Steps", report.steps),
+            Stat::new("Landed on zero", landed),
+            Stat::new(
+                "Passed zero",
+                passed.map_or("too many for a u128".to_string(), |ct| ct.to_string()),
+            ),
+            Stat::new("All wheels at zero", report.all_zero),
+        ],
+    );
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+use std::{
+    any::Any,
+    io::{Cursor, Read},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use crate::{
+    days,
+    parse::{ParseError, ParseMode},
+};
+
+/// How solving an input failed, which has to stay the same while the input is shrunk.
+#[derive(Debug, PartialEq)]
+enum Failure {
+    /// The solver panicked, with this message
+    Panic(String),
+    /// The solver returned an error, with this message
+    Error(String),
+    /// The solver answered differently from the implementation it's checked against
+    Disagreement,
+}
+
+/// The implementation of a part whose input is being minimized.
+pub struct Subject<'a> {
+    pub day: u8,
+    pub part: u8,
+    pub implementation: &'a str,
+    /// Another implementation of the part, which the failing one should agree with
+    pub against: Option<&'a str>,
+}
+
+/// Shrink the input while the `subject` keeps failing in the same way, delta-debugging style,
+/// and write the smallest reproducing input to `output`, or stdout if not given.
+///
+/// Lines are deleted, then grid columns (if every line is as long as the others), then numbers
+/// are shrunk, over and over until none of them makes the input any smaller.
+pub fn run(
+    subject: &Subject,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let Subject { day, part, .. } = *subject;
+    // Otherwise the missing implementation would be the failure that's minimized
+    let names = days::select_puzzle(day)?.implementations(part);
+    for name in std::iter::once(subject.implementation).chain(subject.against) {
+        if !names.contains(&name) {
+            anyhow::bail!(
+                "Day {day}, Part {part} has no implementation named {name:?}, expected one of: {}",
+                names.join(", ")
+            );
+        }
+    }
+
+    let mut raw = String::new();
+    days::get_input(day, profile, input_path)?.read_to_string(&mut raw)?;
+
+    // The solvers are run over and over, so don't print every panic along the way
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let minimized = minimize(&raw, |input| check(subject, mode, input));
+    panic::set_hook(hook);
+    let Some((minimized, failure)) = minimized else {
+        let disagrees = match subject.against {
+            Some(against) => format!(", or disagree with [{against}]"),
+            None => String::new(),
+        };
+        anyhow::bail!(
+            "Day {day}, Part {part} [{}] doesn't panic or error{disagrees} on this input, so \
+             there's nothing to minimize",
+            subject.implementation
+        );
+    };
+
+    eprintln!(
+        "Minimized from {} to {} lines ({} to {} bytes), still failing with {failure:?}",
+        raw.lines().count(),
+        minimized.lines().count(),
+        raw.len(),
+        minimized.len()
+    );
+    match output {
+        Some(path) => std::fs::write(path, minimized)?,
+        None => print!("{minimized}"),
+    }
+
+    Ok(())
+}
+
+/// Solve the `subject`'s part against `input`, returning how it failed if it did.
+///
+/// When checked against another implementation, it only disagrees if that one answers too.
+fn check(subject: &Subject, mode: ParseMode, input: &str) -> Option<Failure> {
+    let solve = |name: &str| {
+        let reader = Box::new(Cursor::new(input.to_string()));
+        let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+            days::solve_with(subject.day, subject.part, name, reader, mode)
+        }));
+        match solved {
+            Ok(Ok(ans)) => Ok(ans.to_string()),
+            // Line and column numbers move as the input shrinks, so only the message has to match
+            Ok(Err(e)) => Err(Failure::Error(
+                match e.chain().find_map(|e| e.downcast_ref::<ParseError>()) {
+                    Some(parse_err) => parse_err.message.clone(),
+                    None => e.to_string(),
+                },
+            )),
+            Err(payload) => Err(Failure::Panic(panic_message(payload.as_ref()))),
+        }
+    };
+
+    let ans = match solve(subject.implementation) {
+        Ok(ans) => ans,
+        Err(failure) => return Some(failure),
+    };
+    let expected = solve(subject.against?).ok()?;
+    (ans != expected).then_some(Failure::Disagreement)
+}
+
+/// Get the message of a panic from its `payload`, which is empty if it wasn't given one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
+/// Shrink `raw` for as long as `check` fails on it in the same way as it did on `raw`.
+///
+/// Returns `None` if `check` doesn't fail on `raw` in the first place.
+fn minimize(raw: &str, check: impl Fn(&str) -> Option<Failure>) -> Option<(String, Failure)> {
+    let failure = check(raw)?;
+    let still_fails = |input: &str| check(input).as_ref() == Some(&failure);
+
+    let mut lines: Vec<String> = raw.lines().map(String::from).collect();
+    loop {
+        let before = join_lines(&lines);
+        lines = remove_chunks(lines, |lines| still_fails(&join_lines(lines)));
+        lines = remove_columns(lines, still_fails);
+        lines = shrink_numbers(lines, still_fails);
+        if join_lines(&lines) == before {
+            break;
+        }
+    }
+
+    Some((join_lines(&lines), failure))
+}
+
+fn join_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Remove as many of the `items` as possible while `still_fails` holds, trying chunks of
+/// decreasing size from half of them down to one at a time.
+fn remove_chunks<T: Clone>(mut items: Vec<T>, still_fails: impl Fn(&[T]) -> bool) -> Vec<T> {
+    let mut chunk = items.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + chunk).min(items.len());
+            let candidate = [&items[..start], &items[end..]].concat();
+            if still_fails(&candidate) {
+                items = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    items
+}
+
+/// Remove whole columns of a grid, if the `lines` form one.
+fn remove_columns(lines: Vec<String>, still_fails: impl Fn(&str) -> bool) -> Vec<String> {
+    let grid: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+    let width = grid.first().map_or(0, Vec::len);
+    if width < 2 || grid.iter().any(|row| row.len() != width) {
+        return lines;
+    }
+
+    let to_lines = |columns: &[usize]| -> Vec<String> {
+        grid.iter()
+            .map(|row| columns.iter().map(|c| row[*c]).collect())
+            .collect()
+    };
+    let columns = remove_chunks((0..width).collect(), |columns| {
+        still_fails(&join_lines(&to_lines(columns)))
+    });
+    to_lines(&columns)
+}
+
+/// Replace every number in the `lines` with the smallest of a few smaller candidates which
+/// keeps `still_fails` holding.
+fn shrink_numbers(mut lines: Vec<String>, still_fails: impl Fn(&str) -> bool) -> Vec<String> {
+    for idx in 0..lines.len() {
+        let mut pos = 0;
+        while let Some(start) = lines[idx][pos..]
+            .find(|c: char| c.is_ascii_digit())
+            .map(|offset| pos + offset)
+        {
+            let line = &lines[idx];
+            let end = line[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(line.len(), |offset| start + offset);
+            let num = line[start..end].to_string();
+
+            let smaller = smaller_numbers(&num).into_iter().find(|candidate| {
+                let mut candidate_lines = lines.clone();
+                candidate_lines[idx].replace_range(start..end, candidate);
+                still_fails(&join_lines(&candidate_lines))
+            });
+            match smaller {
+                // Stay on this number, in case it can shrink any further
+                Some(smaller) => lines[idx].replace_range(start..end, &smaller),
+                None => pos = end,
+            }
+        }
+    }
+    lines
+}
+
+/// Candidates for shrinking `num`, all of which are strictly smaller so that shrinking ends.
+fn smaller_numbers(num: &str) -> Vec<String> {
+    let mut candidates = vec!["0".to_string(), "1".to_string()];
+    if num.len() > 1 {
+        candidates.push(num[..num.len() - 1].to_string());
+    }
+    if let Ok(value) = num.parse::<u128>() {
+        candidates.push((value / 2).to_string());
+        candidates.push(value.saturating_sub(1).to_string());
+    }
+
+    let mut smaller: Vec<String> = vec![];
+    for candidate in candidates {
+        if (candidate.len(), candidate.as_str()) < (num.len(), num) && !smaller.contains(&candidate)
+        {
+            smaller.push(candidate);
+        }
+    }
+    smaller
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize() {
+        // Fails on any line holding a number of at least 500, next to a line holding an `x`
+        let check = |input: &str| {
+            let lines: Vec<&str> = input.lines().collect();
+            let fails = lines.windows(2).any(|pair| {
+                pair[0].parse::<u32>().is_ok_and(|num| num >= 500) && pair[1].contains('x')
+            });
+            fails.then(|| Failure::Panic("boom".to_string()))
+        };
+        let raw = "1\n2\n3\n12345\nabxcd\n4\n5\n";
+        let (minimized, failure) = minimize(raw, check).unwrap();
+        // Both lines are 5 long at first, so a column of the grid they form goes too
+        assert_eq!("500\nbxcd\n", minimized);
+        assert_eq!(Failure::Panic("boom".to_string()), failure);
+
+        assert!(minimize("1\n2\n", check).is_none());
+    }
+
+    #[test]
+    fn test_check() {
+        let subject = Subject {
+            day: 1,
+            part: 2,
+            implementation: "default",
+            against: Some("clicks"),
+        };
+        assert_eq!(None, check(&subject, ParseMode::Strict, "L68\nR30\n"));
+        assert_eq!(
+            Some(Failure::Error(
+                "Rotation direction should be L or R".to_string()
+            )),
+            check(&subject, ParseMode::Strict, "L68\nX30\n")
+        );
+    }
+
+    #[test]
+    fn test_remove_columns() {
+        let lines = vec!["..@.".to_string(), "@...".to_string()];
+        let minimized = remove_columns(lines, |input| input.matches('@').count() == 2);
+        assert_eq!(vec![".@", "@."], minimized);
+    }
+}
@@ -0,0 +1,14 @@
+//! Implementations of each of the CLI's actions, one submodule per action.
+
+pub mod crosscheck;
+pub mod generate;
+pub mod ids;
+pub mod inspect;
+pub mod lock;
+pub mod minimize;
+pub mod online;
+pub mod profiles;
+pub mod run;
+pub mod scaling;
+pub mod trace;
+pub mod validate;
@@ -0,0 +1,57 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    days,
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+};
+
+/// The day whose dial is turned by the stream of rotations.
+const DAY: u8 = 1;
+
+/// Turn day 1's dial by rotations read from stdin for as long as they keep coming, printing the
+/// running answers to both parts every `interval` rotations.
+///
+/// If `input_path` or a `profile` is given, the rotations are read from that input file instead,
+/// as with every other command. Skipped lines are only counted in lenient mode, so the memory
+/// used stays constant either way.
+pub fn run(
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+    interval: u64,
+) -> anyhow::Result<()> {
+    let from_file = input_path.is_some() || profile.is_some();
+    let input: Box<dyn BufRead> = if from_file {
+        days::get_input(DAY, profile, input_path)?
+    } else {
+        Box::new(std::io::stdin().lock())
+    };
+    let puzzle = days::select_puzzle(DAY)?;
+    let input = NormalizedInput::new(input, puzzle.normalization, mode).streaming();
+    let line_map = input.line_map();
+
+    let mut stdout = std::io::stdout().lock();
+    days::count_online(input, interval, |count| {
+        writeln!(
+            stdout,
+            "Rotations: {}, Part 1: {}, Part 2: {}",
+            count.rotations, count.part_one, count.part_two
+        )?;
+        // Flushed straight away, since the next report may be a long time coming
+        stdout.flush()?;
+        Ok(())
+    })
+    .map_err(|e| parse::remap_lines(e, &line_map))
+    .map_err(|e| {
+        let path = if from_file {
+            days::get_input_path(DAY, profile, input_path)
+        } else {
+            PathBuf::from("<stdin>")
+        };
+        parse::report(e, &path)
+    })
+}
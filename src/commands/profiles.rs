@@ -0,0 +1,64 @@
+use crate::{answers::AnswerStore, days, parse::ParseMode};
+
+const DEFAULT_PROFILE_NAME: &str = "(default)";
+
+/// Run both parts of `day` against the input of every profile which has one, and print
+/// a table of the answers.
+///
+/// An answer which disagrees with that profile's recorded answer is marked with a `!`.
+pub fn run(day: u8, mode: ParseMode) -> anyhow::Result<()> {
+    let profiles = std::iter::once(None).chain(days::list_profiles()?.into_iter().map(Some));
+
+    let mut rows = vec![];
+    for profile in profiles {
+        let profile = profile.as_deref();
+        if !days::input_path_for_day(day, profile).exists() {
+            continue;
+        }
+
+        let store = AnswerStore::load(profile)?;
+        let mut row = vec![profile.unwrap_or(DEFAULT_PROFILE_NAME).to_string()];
+        for part in 1..=2 {
+            let input = days::get_input_for_day(day, profile)?;
+            let cell = match days::solve(day, part, input, mode) {
+                Ok(ans) => {
+                    let ans = ans.to_string();
+                    match store.get(day, part) {
+                        Some(recorded) if recorded != ans => format!("{ans} !"),
+                        _ => ans,
+                    }
+                }
+                Err(e) => format!("error: {e:#}"),
+            };
+            row.push(cell);
+        }
+        rows.push(row);
+    }
+
+    if rows.is_empty() {
+        anyhow::bail!("No profile has an input for day {day}");
+    }
+
+    let header = ["Profile", "Part 1", "Part 2"].map(String::from).to_vec();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[col].len())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    println!("Day {day}");
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", cells.join(" | ").trim_end());
+    }
+
+    Ok(())
+}
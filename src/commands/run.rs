@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use crate::{
+    answers::AnswerStore,
+    cli::RunArgs,
+    days,
+    parse::{self, ParseMode},
+};
+
+/// Run a single part of a day, optionally recording the answer in the profile's store.
+///
+/// The input is read from `input_path` if given, otherwise from the profile's input for the day.
+/// Answers for an explicit `input_path` aren't checked against or recorded in the store, since
+/// they don't belong to any profile.
+pub fn run(
+    args: RunArgs,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let RunArgs {
+        day,
+        part,
+        save,
+        implementation,
+    } = args;
+    let day = day.expect("clap should require a day when no subcommand is given");
+    let part = part.expect("clap should require a part when no subcommand is given");
+
+    let input = days::get_input(day, profile, input_path)?;
+    for warning in days::lint(day, input, mode)? {
+        eprintln!("Warning: {warning}");
+    }
+
+    let input = days::get_input(day, profile, input_path)?;
+    let ans = days::solve_with(day, part, &implementation, input, mode)
+        .map_err(|e| parse::report(e, &days::get_input_path(day, profile, input_path)))?
+        .to_string();
+
+    println!("Answer for Day {day}, Part {part}: {ans}");
+
+    if input_path.is_some() {
+        if save {
+            eprintln!("Warning: not saving answer for an input given with --input");
+        }
+        return Ok(());
+    }
+
+    let mut store = AnswerStore::load(profile)?;
+    if let Some(recorded) = store.get(day, part)
+        && recorded != ans
+    {
+        eprintln!("Warning: answer differs from the recorded answer {recorded}");
+    }
+    if save {
+        store.record(day, part, ans);
+        store.save()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use std::{
+    io::Cursor,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use crate::{days, parse::ParseMode, rng::Rng};
+
+/// Time `part` of `day` against generated inputs of geometrically increasing sizes, and fit
+/// the growth of the runtime against the input length to estimate its complexity.
+///
+/// Each size is timed `repeats` times and the fastest run is kept, to cut down on noise.
+pub fn run(
+    day: u8,
+    part: u8,
+    min_size: usize,
+    steps: u32,
+    factor: u64,
+    seed: u64,
+    repeats: u32,
+) -> anyhow::Result<()> {
+    // Every size is worked out up front, so that one which is too large fails before any timing
+    let sizes = (0..steps)
+        .map(|step| {
+            factor
+                .checked_pow(step)
+                .and_then(|growth| growth.checked_mul(min_size as u64))
+                .and_then(|size| usize::try_from(size).ok())
+        })
+        .collect::<Option<Vec<usize>>>()
+        .with_context(|| {
+            format!("Input sizes grow too large over {steps} steps with a factor of {factor}")
+        })?;
+
+    if cfg!(debug_assertions) {
+        eprintln!("Warning: timing a debug build, consider running with --release");
+    }
+
+    println!("{:>10}  {:>12}  {:>12}", "Size", "Bytes", "Time");
+    let mut samples = vec![];
+    for size in sizes {
+        let mut input = vec![];
+        (days::select_puzzle(day)?.generate)(&mut Rng::new(seed), size, &mut input)?;
+
+        let mut fastest = Duration::MAX;
+        for _ in 0..repeats.max(1) {
+            let reader = Box::new(Cursor::new(input.clone()));
+            let start = Instant::now();
+            days::solve(day, part, reader, ParseMode::Strict)?;
+            fastest = fastest.min(start.elapsed());
+        }
+
+        println!("{size:>10}  {:>12}  {:>12.3?}", input.len(), fastest);
+        samples.push((input.len() as f64, fastest.as_secs_f64()));
+    }
+
+    let Some((exponent, r_squared)) = fit_power_law(&samples) else {
+        anyhow::bail!("Need at least two distinct, non-zero samples to fit a growth curve");
+    };
+    let class = match exponent.round() as i64 {
+        ..=0 => "O(1)".to_string(),
+        1 => "O(n)".to_string(),
+        k => format!("O(n^{k})"),
+    };
+    println!(
+        "Estimated exponent {exponent:.2} (R^2 = {r_squared:.3}), roughly {class} in the input length"
+    );
+
+    Ok(())
+}
+
+/// Fit `y = c * x^k` to the `samples` by least squares on their logarithms.
+///
+/// Returns the exponent `k` along with the R^2 of the fit, or `None` if there are too few
+/// usable samples.
+fn fit_power_law(samples: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter(|(x, y)| *x > 0.0 && *y > 0.0)
+        .map(|(x, y)| (x.ln(), y.ln()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let var_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let var_y: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let cov: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    if points.len() < 2 || var_x == 0.0 {
+        return None;
+    }
+
+    let slope = cov / var_x;
+    let r_squared = if var_y == 0.0 {
+        1.0
+    } else {
+        cov * cov / (var_x * var_y)
+    };
+    Some((slope, r_squared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_power_law() {
+        let samples: Vec<_> = (1..=6)
+            .map(|i| {
+                let x = 10f64.powi(i);
+                (x, 3.0 * x * x)
+            })
+            .collect();
+        let (exponent, r_squared) = fit_power_law(&samples).unwrap();
+        assert!((exponent - 2.0).abs() < 1e-9);
+        assert!((r_squared - 1.0).abs() < 1e-9);
+
+        assert!(fit_power_law(&samples[..1]).is_none());
+    }
+}
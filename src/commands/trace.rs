@@ -0,0 +1,45 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    days,
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+    trace::{TraceFormat, TraceWriter},
+};
+
+/// Write a trace of every step that the solution for `day` takes through its input to
+/// `output`, or stdout if not given.
+///
+/// If the input can't be parsed partway through, the steps before the failure are still written.
+pub fn run(
+    day: u8,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+    format: TraceFormat,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let input = days::get_input(day, profile, input_path)?;
+    let puzzle = days::select_puzzle(day)?;
+    let Some(trace) = puzzle.trace else {
+        anyhow::bail!("Day {day} doesn't support tracing");
+    };
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+
+    let input = NormalizedInput::new(input, puzzle.normalization, mode);
+    let line_map = input.line_map();
+    let mut writer = TraceWriter::new(&mut out, format, line_map.clone());
+    let traced = trace(Box::new(input), &mut writer);
+    // Close off the records written before any failure, so the trace is still well-formed
+    writer.finish()?;
+    traced
+        .map_err(|e| parse::remap_lines(e, &line_map))
+        .map_err(|e| parse::report(e, &days::get_input_path(day, profile, input_path)))
+}
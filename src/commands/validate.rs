@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use crate::{days, normalize::NormalizedInput, parse::ParseMode};
+
+/// Check the input for `day` against the shape that the day expects, printing every violation.
+///
+/// The input is normalized first, so that it's checked as the solvers would see it.
+pub fn run(
+    day: u8,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+    mode: ParseMode,
+) -> anyhow::Result<()> {
+    let input = days::get_input(day, profile, input_path)?;
+    let path = days::get_input_path(day, profile, input_path);
+
+    let puzzle = days::select_puzzle(day)?;
+    let input = NormalizedInput::new(input, puzzle.normalization, mode);
+    let line_map = input.line_map();
+    let mut violations = (puzzle.validate)(Box::new(input))?;
+    for violation in &mut violations {
+        violation.line = line_map.original_line(violation.line);
+    }
+    violations.sort_by_key(|v| (v.line, v.column));
+
+    if violations.is_empty() {
+        println!("Input for Day {day} is valid");
+        return Ok(());
+    }
+    for violation in &violations {
+        println!("{}:{violation}", path.display());
+    }
+    anyhow::bail!(
+        "Found {} problem(s) with the input for Day {day}",
+        violations.len()
+    )
+}
@@ -1,155 +1,607 @@
 use std::{
-    io::BufRead,
+    fmt::Display,
+    io::{BufRead, Write},
     ops::{Add, Sub},
     str::FromStr,
 };
 
 use anyhow::Context;
 
-use crate::puzzle::Puzzle;
+use crate::{
+    inspect::{Stat, summarize},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::{Implementation, Puzzle},
+    rng::Rng,
+    trace::{Field, TraceWriter},
+    validate::{Violation, check_unsigned},
+};
 
 pub fn get_puzzle() -> Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![Implementation {
+            name: "clicks",
+            part: 2,
+            solver: Box::new(|input| Ok(Box::new(_part_two_clicks(input, Dial::START)?))),
+        }],
+        normalization: Normalization::default(),
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: Some(Box::new(trace)),
     }
 }
 
+/// The puzzle's dial, which has 100 positions
+type Dial = SafeNum<100>;
+
+impl Dial {
+    /// Where the puzzle's dial points before the first rotation
+    const START: Self = Self(50);
+}
+
 fn part_one(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    let ans = _part_one(input)?;
+    let ans = _part_one(input, Dial::START)?;
     Ok(Box::new(ans))
 }
 
 fn part_two(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    let ans = _part_two(input)?;
+    let ans = _part_two(input, Dial::START)?;
     Ok(Box::new(ans))
 }
 
-fn _part_one(input: impl BufRead) -> anyhow::Result<u32> {
-    let mut pointer = SafeNum::default();
+/// Count the rotations which leave a dial of `N` positions, starting at `start`, pointing at zero.
+fn _part_one<const N: u64>(input: impl BufRead, start: SafeNum<N>) -> anyhow::Result<u32> {
     let mut zero_ct = 0;
+    turn_dial(input, start, |step| {
+        if step.after == 0 {
+            zero_ct += 1;
+        }
+        Ok(())
+    })?;
+    Ok(zero_ct)
+}
 
-    for line in input.lines() {
-        let line = line?;
-        let (dir, mag) = line.split_at(1);
+/// A single rotation of the dial, and where it left the dial.
+struct Step<const N: u64> {
+    line_no: usize,
+    dir: Direction,
+    mag: u128,
+    before: SafeNum<N>,
+    after: SafeNum<N>,
+    /// How many times the dial pointed at zero during the rotation, see [`SafeNum::zeros_passed`]
+    zeros_passed: u128,
+}
 
-        let mag = mag
-            .parse::<SafeNum>()
-            .context("Could not parse valid magnitude int")?;
+/// Turn a dial of `N` positions, starting at `start`, through every rotation in the `input`,
+/// handing each step to `on_step` as it's taken.
+///
+/// Both parts and the trace are driven by this, so that they can't disagree on any step.
+fn turn_dial<const N: u64>(
+    input: impl BufRead,
+    start: SafeNum<N>,
+    mut on_step: impl FnMut(&Step<N>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut pointer = start;
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        let (dir, mag) = parse_rotation(line_no, &line)?;
+
+        let step = Step {
+            line_no,
+            dir,
+            mag,
+            before: pointer,
+            after: pointer.rotate(dir, mag),
+            zeros_passed: pointer.zeros_passed(dir, mag),
+        };
+        on_step(&step)?;
+        pointer = step.after;
+    }
+    Ok(())
+}
 
-        match dir {
-            "L" => pointer = pointer - mag,
-            "R" => pointer = pointer + mag,
-            _ => unreachable!("dir should be only L or R; actually is {}", dir),
+/// Write a record of every rotation of the puzzle's dial.
+fn trace(input: Box<dyn BufRead>, writer: &mut TraceWriter) -> anyhow::Result<()> {
+    turn_dial(input, Dial::START, |step| {
+        let dir = match step.dir {
+            Direction::Left => "L",
+            Direction::Right => "R",
         };
+        writer.record(
+            step.line_no,
+            &[
+                ("direction", Field::Text(dir.to_string())),
+                ("magnitude", Field::Int(step.mag)),
+                ("before", Field::Int(step.before.0.into())),
+                ("after", Field::Int(step.after.0.into())),
+                ("zeros_passed", Field::Int(step.zeros_passed)),
+                ("landed_on_zero", Field::Bool(step.after == 0)),
+            ],
+        )?;
+        Ok(())
+    })
+}
 
-        if pointer == 0 {
-            zero_ct += 1;
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Left,
+    Right,
+}
+
+/// Parse a rotation from its `line` of the input, which should be `L` or `R` followed by its
+/// magnitude.
+fn parse_rotation<T: FromStr>(line_no: usize, line: &str) -> Result<(Direction, T), ParseError> {
+    let mut chars = line.chars();
+    let dir = match chars.next() {
+        Some('L') => Direction::Left,
+        Some('R') => Direction::Right,
+        Some(c) => {
+            return Err(ParseError::at(
+                "Rotation direction should be L or R",
+                line_no,
+                line,
+                &line[..c.len_utf8()],
+            ));
         }
-    }
+        None => return Err(ParseError::after("Expected a rotation", line_no, line)),
+    };
+    let mag = parse_magnitude(line_no, line, chars.as_str())?;
+    Ok((dir, mag))
+}
 
-    Ok(zero_ct)
+/// Parse the magnitude `mag` of a rotation, which is a subslice of its `line`.
+fn parse_magnitude<T: FromStr>(line_no: usize, line: &str, mag: &str) -> Result<T, ParseError> {
+    mag.parse()
+        .map_err(|_| ParseError::at("Could not parse valid magnitude int", line_no, line, mag))
 }
 
+/// A position on a dial with `N` positions, numbered `0..N`, which wraps around in both
+/// directions.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
-struct SafeNum(u8);
+struct SafeNum<const N: u64>(u64);
+
+impl<const N: u64> SafeNum<N> {
+    /// Max value to bound rotation
+    const MAX: u64 = N;
+
+    /// Create the position `value`, wrapped around the dial.
+    fn new(value: u64) -> Self {
+        const { assert!(N > 0, "a dial needs at least one position") };
+        Self(value % N)
+    }
+
+    /// The same position on a [`DialPos`], which does the arithmetic for dials of every size.
+    fn dial_pos(self) -> DialPos {
+        DialPos {
+            size: N,
+            pos: self.0,
+        }
+    }
+
+    /// Turn the dial `mag` clicks in the direction `dir`.
+    fn rotate(self, dir: Direction, mag: u128) -> Self {
+        Self(self.dial_pos().rotate(dir, mag).pos)
+    }
 
-impl Default for SafeNum {
-    fn default() -> Self {
-        Self(50)
+    /// Count how many times the dial points at zero while turning `mag` clicks in the
+    /// direction `dir`, including where it ends up but not where it starts.
+    fn zeros_passed(self, dir: Direction, mag: u128) -> u128 {
+        self.dial_pos().zeros_passed(dir, mag)
     }
 }
 
-impl SafeNum {
-    /// Max value to bound rotation
-    const MAX: u8 = 100;
+impl<const N: u64> Add for SafeNum<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self((self.dial_pos() + rhs.dial_pos()).pos)
+    }
 }
 
-impl FromStr for SafeNum {
-    type Err = anyhow::Error;
+impl<const N: u64> Sub for SafeNum<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self((self.dial_pos() - rhs.dial_pos()).pos)
+    }
+}
+
+impl<const N: u64> PartialEq<u64> for SafeNum<N> {
+    fn eq(&self, other: &u64) -> bool {
+        &self.0 == other
+    }
+}
+
+/// A position on a dial whose number of positions is only known at runtime, ie a wheel of a
+/// lock, which wraps around in both directions like a [`SafeNum`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DialPos {
+    size: u64,
+    pos: u64,
+}
+
+impl DialPos {
+    /// Create the position `value` on a dial of `size` positions, wrapped around the dial.
+    fn new(size: u64, value: u64) -> Self {
+        assert!(size > 0, "a dial needs at least one position");
+        Self {
+            size,
+            pos: value % size,
+        }
+    }
+
+    /// Turn the dial `mag` clicks in the direction `dir`, see [`SafeNum::rotate`].
+    fn rotate(self, dir: Direction, mag: u128) -> Self {
+        let mag = Self::new(self.size, (mag % self.size as u128) as u64);
+        match dir {
+            Direction::Left => self - mag,
+            Direction::Right => self + mag,
+        }
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let raw_int = s
-            .parse::<usize>()
-            .context("Cannot parse SafeNum candidate string as number")?;
-        let wrapped = raw_int % Self::MAX as usize;
-        Ok(Self(wrapped as u8))
+    /// Count how many times the dial points at zero while turning `mag` clicks in the
+    /// direction `dir`, see [`SafeNum::zeros_passed`].
+    fn zeros_passed(self, dir: Direction, mag: u128) -> u128 {
+        let full_turns = mag / self.size as u128;
+        // What's left is less than a full turn, so the positions travelled over fit in an i128
+        let rest = (mag % self.size as u128) as i128;
+        let pos = i128::from(self.pos);
+        let n = i128::from(self.size);
+
+        // Zero is reached once for every multiple of n in the positions (lo, hi] travelled over,
+        // counting them without wrapping around the dial
+        let (lo, hi) = match dir {
+            Direction::Left => (pos - rest - 1, pos - 1),
+            Direction::Right => (pos, pos + rest),
+        };
+        full_turns + (hi.div_euclid(n) - lo.div_euclid(n)) as u128
     }
 }
 
-impl Add for SafeNum {
+impl Add for DialPos {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let sum = (self.0 + rhs.0) % Self::MAX;
-        Self(sum)
+        debug_assert_eq!(self.size, rhs.size, "positions should be on the same dial");
+        // Widened, since the sum of two positions on a large enough dial won't fit a `u64`
+        let pos = (self.pos as u128 + rhs.pos as u128) % self.size as u128;
+        Self {
+            size: self.size,
+            pos: pos as u64,
+        }
     }
 }
 
-impl Sub for SafeNum {
+impl Sub for DialPos {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let diff = if self.0 >= rhs.0 {
-            self.0 - rhs.0
+        debug_assert_eq!(self.size, rhs.size, "positions should be on the same dial");
+        let pos = if self.pos >= rhs.pos {
+            self.pos - rhs.pos
         } else {
-            Self::MAX - (rhs.0 - self.0)
+            self.size - (rhs.pos - self.pos)
         };
-        Self(diff)
+        Self {
+            size: self.size,
+            pos,
+        }
     }
 }
 
-impl PartialEq<u8> for SafeNum {
-    fn eq(&self, other: &u8) -> bool {
-        &self.0 == other
+impl PartialEq<u64> for DialPos {
+    fn eq(&self, other: &u64) -> bool {
+        &self.pos == other
+    }
+}
+
+/// Count every time that a dial of `N` positions, starting at `start`, points at zero during
+/// any of the rotations.
+fn _part_two<const N: u64>(input: impl BufRead, start: SafeNum<N>) -> anyhow::Result<u64> {
+    let mut zero_ct: u64 = 0;
+    turn_dial(input, start, |step| {
+        zero_ct = add_zeros_passed(zero_ct, step)?;
+        Ok(())
+    })?;
+    Ok(zero_ct)
+}
+
+/// Add the zeros passed during the `step` to the count of them so far, `zero_ct`.
+fn add_zeros_passed<const N: u64>(zero_ct: u64, step: &Step<N>) -> anyhow::Result<u64> {
+    u64::try_from(step.zeros_passed)
+        .ok()
+        .and_then(|passed| zero_ct.checked_add(passed))
+        .with_context(|| {
+            format!(
+                "Count of zeros passed is too large for a u64, on line {}",
+                step.line_no
+            )
+        })
+}
+
+/// The answers to both parts so far, while rotations are still coming in.
+#[derive(Debug, Default, PartialEq)]
+pub struct RunningCount {
+    pub rotations: u64,
+    pub part_one: u64,
+    pub part_two: u64,
+}
+
+/// Turn the puzzle's dial through a stream of rotations which may never end, handing the
+/// running counts to `report` after every `interval` rotations and once more at the end of the
+/// input.
+///
+/// Only the dial's position and the counts are kept between rotations, so the memory used
+/// doesn't grow with the input.
+pub fn count_online(
+    input: impl BufRead,
+    interval: u64,
+    mut report: impl FnMut(&RunningCount) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut count = RunningCount::default();
+    turn_dial(input, Dial::START, |step| {
+        count.rotations += 1;
+        if step.after == 0 {
+            count.part_one += 1;
+        }
+        count.part_two = add_zeros_passed(count.part_two, step)?;
+
+        if count.rotations % interval == 0 {
+            report(&count)?;
+        }
+        Ok(())
+    })?;
+
+    if count.rotations == 0 || count.rotations % interval != 0 {
+        report(&count)?;
     }
+    Ok(())
 }
 
-fn _part_two(input: impl BufRead) -> anyhow::Result<u32> {
-    let mut pointer = SafeNum::default();
+/// Count the zero crossings for part two by turning the dial one click at a time.
+fn _part_two_clicks<const N: u64>(input: impl BufRead, start: SafeNum<N>) -> anyhow::Result<u64> {
+    let mut pointer = start;
     let mut zero_ct = 0;
 
-    for line in input.lines() {
+    for (idx, line) in input.lines().enumerate() {
+        let (dir, mag): (_, u128) = parse_rotation(idx + 1, &line?)?;
+        for _ in 0..mag {
+            pointer = match dir {
+                Direction::Left => pointer - SafeNum::new(1),
+                Direction::Right => pointer + SafeNum::new(1),
+            };
+            if pointer == 0 {
+                zero_ct += 1;
+            }
+        }
+    }
+
+    Ok(zero_ct)
+}
+
+/// A wheel of a combination lock, which is a dial like the puzzle's but with any number of
+/// positions, given as `SIZE@START`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wheel {
+    pub size: u64,
+    pub start: u64,
+}
+
+impl FromStr for Wheel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (size, start) = s
+            .split_once('@')
+            .with_context(|| format!("Wheel should be given as SIZE@START, not {s:?}"))?;
+        let size: u64 = size
+            .parse()
+            .with_context(|| format!("Could not parse wheel size {size:?}"))?;
+        let start: u64 = start
+            .parse()
+            .with_context(|| format!("Could not parse wheel start {start:?}"))?;
+        if size == 0 {
+            anyhow::bail!("A wheel needs at least one position");
+        }
+        if start >= size {
+            anyhow::bail!("Wheel start {start} should be less than its size {size}");
+        }
+        Ok(Self { size, start })
+    }
+}
+
+impl Wheel {
+    /// Where the wheel points before the first rotation.
+    fn start_pos(&self) -> DialPos {
+        DialPos::new(self.size, self.start)
+    }
+}
+
+impl Display for Wheel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.size, self.start)
+    }
+}
+
+/// Zero statistics for a single wheel of a lock.
+#[derive(Debug, PartialEq)]
+pub struct WheelReport {
+    pub wheel: Wheel,
+    pub rotations: usize,
+    /// Rotations which left the wheel pointing at zero, as in part one
+    pub landed: usize,
+    /// Times the wheel pointed at zero during any rotation, as in part two
+    pub passed: u128,
+}
+
+/// Zero statistics for a whole lock, after every instruction has been followed.
+#[derive(Debug, PartialEq)]
+pub struct LockReport {
+    pub wheels: Vec<WheelReport>,
+    /// Instructions followed, each of which turns a single wheel
+    pub steps: usize,
+    /// Steps after which every wheel pointed at zero at once
+    pub all_zero: usize,
+}
+
+/// Turn a combination lock with the given `wheels` by every instruction in the `input`.
+///
+/// Each instruction is a rotation prefixed by the 1-based number of the wheel that it turns, ie
+/// `2:L15`. A rotation without a prefix turns the first wheel, so the puzzle's input is a valid
+/// one for a single `100@50` wheel.
+pub fn simulate_lock(input: impl BufRead, wheels: &[Wheel]) -> anyhow::Result<LockReport> {
+    let mut positions: Vec<DialPos> = wheels.iter().map(Wheel::start_pos).collect();
+    let mut report = LockReport {
+        wheels: wheels
+            .iter()
+            .map(|wheel| WheelReport {
+                wheel: *wheel,
+                rotations: 0,
+                landed: 0,
+                passed: 0,
+            })
+            .collect(),
+        steps: 0,
+        all_zero: 0,
+    };
+
+    for (idx, line) in input.lines().enumerate() {
         let line = line?;
-        let (dir, mag) = line.split_at(1);
+        let line_no = idx + 1;
+        let (wheel_idx, dir, mag) = parse_instruction(line_no, &line, wheels.len())?;
+
+        let wheel = &mut report.wheels[wheel_idx];
+        wheel.passed = wheel
+            .passed
+            .checked_add(positions[wheel_idx].zeros_passed(dir, mag))
+            .with_context(|| format!("Count of zeros passed is too large, on line {line_no}"))?;
+        positions[wheel_idx] = positions[wheel_idx].rotate(dir, mag);
+        wheel.rotations += 1;
+        if positions[wheel_idx] == 0 {
+            wheel.landed += 1;
+        }
 
-        let mag = mag
-            .parse::<u32>()
-            .context("Could not parse valid magnitude int")?;
+        report.steps += 1;
+        if positions.iter().all(|pos| *pos == 0) {
+            report.all_zero += 1;
+        }
+    }
 
-        // Zero is passed one time for each full rotation
-        let div = mag / SafeNum::MAX as u32;
-        zero_ct += div;
+    Ok(report)
+}
 
-        let mag = SafeNum((mag % SafeNum::MAX as u32) as u8);
+/// Parse an instruction for a lock of `wheel_ct` wheels into the 0-based index of the wheel
+/// that it turns and its rotation.
+fn parse_instruction(
+    line_no: usize,
+    line: &str,
+    wheel_ct: usize,
+) -> Result<(usize, Direction, u128), ParseError> {
+    let (wheel_idx, rotation) = match line.split_once(':') {
+        Some((wheel, rotation)) => {
+            let wheel_idx = wheel
+                .parse::<usize>()
+                .ok()
+                .filter(|wheel| (1..=wheel_ct).contains(wheel))
+                .ok_or_else(|| {
+                    ParseError::at(
+                        format!("Wheel should be a number from 1 to {wheel_ct}"),
+                        line_no,
+                        line,
+                        wheel,
+                    )
+                })?;
+            (wheel_idx - 1, rotation)
+        }
+        None => (0, line),
+    };
+
+    // Point any error at the rotation's place in the whole line
+    let offset = line.len() - rotation.len();
+    let (dir, mag) = parse_rotation(line_no, rotation).map_err(|mut e| {
+        e.source_line = line.to_string();
+        e.span = e.span.start + offset..e.span.end + offset;
+        e
+    })?;
+    Ok((wheel_idx, dir, mag))
+}
 
-        match dir {
-            "L" => {
-                let new_ptr = pointer - mag;
-                if (new_ptr > pointer && pointer != 0) || new_ptr == 0 {
-                    zero_ct += 1;
-                }
-                pointer = new_ptr;
-            }
-            "R" => {
-                let new_ptr = pointer + mag;
-                if (new_ptr < pointer && pointer != 0) || new_ptr == 0 {
-                    zero_ct += 1;
-                }
-                pointer = new_ptr;
+/// Each line should be a rotation, ie `L` or `R` followed by its magnitude.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        let mut chars = line.chars();
+        match chars.next() {
+            Some('L' | 'R') => {
+                check_unsigned::<u128>(line_no, 2, chars.as_str(), "magnitude", &mut violations);
             }
-            _ => unreachable!("dir should be only L or R; actually is {}", dir),
+            Some(c) => violations.push(Violation::new(
+                line_no,
+                1,
+                format!("unexpected {c:?}, expected 'L' or 'R'"),
+            )),
+            None => violations.push(Violation::new(
+                line_no,
+                1,
+                "empty line, expected a rotation",
+            )),
+        }
+    }
+    Ok(violations)
+}
+
+/// Rotation counts by direction, and how large their magnitudes are.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let (mut left_ct, mut right_ct) = (0, 0);
+    let mut mags = vec![];
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let (dir, mag): (_, u128) = parse_rotation(idx + 1, &line)?;
+
+        match dir {
+            Direction::Left => left_ct += 1,
+            Direction::Right => right_ct += 1,
         };
+        mags.push(mag);
     }
 
-    Ok(zero_ct)
+    let full_turns = mags
+        .iter()
+        .filter(|mag| **mag >= u128::from(Dial::MAX))
+        .count();
+
+    Ok(vec![
+        Stat::new("Rotations", mags.len()),
+        Stat::new("Left / right", format!("{left_ct} / {right_ct}")),
+        Stat::new("Magnitude", summarize(mags)),
+        Stat::new("Full turns or more", full_turns),
+    ])
+}
+
+/// `size` rotations, each with a random direction and a magnitude of up to ten full turns.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..size {
+        let dir = if rng.chance(0.5) { 'L' } else { 'R' };
+        let mag = rng.range(1..=10 * Dial::MAX as usize);
+        writeln!(out, "{dir}{mag}")?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::BufReader;
+    use std::io::{BufReader, Cursor};
 
     use super::*;
+    use crate::{differential, fuzz, normalize::LineMap, trace::TraceFormat};
 
     const TEST_INPUT: &str = "L68
 L30
@@ -166,15 +618,238 @@ L82";
     fn test_part_one() {
         let expected: u32 = 3;
         let input_reader = BufReader::new(TEST_INPUT.as_bytes());
-        let actual = _part_one(input_reader).unwrap();
+        let actual = _part_one(input_reader, Dial::START).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_part_two() {
-        let expected: u32 = 6;
+        let expected: u64 = 6;
         let input_reader = BufReader::new(TEST_INPUT.as_bytes());
-        let actual = _part_two(input_reader).unwrap();
+        let actual = _part_two(input_reader, Dial::START).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_inspect() {
+        let stats = inspect(Box::new("L5\nR50000000000000000000000\n".as_bytes())).unwrap();
+        let magnitude = stats.iter().find(|stat| stat.name == "Magnitude").unwrap();
+        assert!(magnitude.value.ends_with("max 50000000000000000000000"));
+        let full_turns = stats
+            .iter()
+            .find(|stat| stat.name == "Full turns or more")
+            .unwrap();
+        assert_eq!("1", full_turns.value);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            validate(Box::new(TEST_INPUT.as_bytes()))
+                .unwrap()
+                .is_empty()
+        );
+
+        let expected = vec![
+            Violation::new(2, 1, "unexpected 'X', expected 'L' or 'R'"),
+            Violation::new(3, 1, "empty line, expected a rotation"),
+            Violation::new(4, 3, "unexpected 'x' in magnitude, expected a digit"),
+        ];
+        let actual = validate(Box::new("L68\nX30\n\nR4x\n".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(1), 500, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input_reader = BufReader::new("L68\nR3o".as_bytes());
+        let err = _part_one(input_reader, Dial::START).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 2, "3o"), (err.line, err.column(), err.text()));
+
+        let err = parse_rotation::<u32>(3, "").unwrap_err();
+        assert_eq!((3, 1), (err.line, err.column()));
+        let err = parse_rotation::<u32>(1, "X30").unwrap_err();
+        assert_eq!((1, 1, "X"), (err.line, err.column(), err.text()));
+    }
+
+    /// Random rotations for a dial of `N` positions, favouring exact multiples of a full turn
+    /// which are easy to get wrong.
+    fn generate_rotations<const N: u64>(rng: &mut Rng) -> String {
+        let mut input = String::new();
+        for _ in 0..rng.range(1..=20) {
+            let dir = if rng.chance(0.5) { 'L' } else { 'R' };
+            let mag = if rng.chance(0.2) {
+                rng.range(0..=3) * N as usize
+            } else {
+                rng.range(0..=(N as usize * 5 / 2))
+            };
+            input.push_str(&format!("{dir}{mag}\n"));
+        }
+        input
+    }
+
+    fn check_part_two_differential<const N: u64>(start: u64) {
+        let start = SafeNum::<N>::new(start);
+        differential::run(
+            200,
+            generate_rotations::<N>,
+            |input| _part_two(input.as_bytes(), start).unwrap(),
+            |input| _part_two_clicks(input.as_bytes(), start).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_part_two_differential() {
+        check_part_two_differential::<100>(50);
+        check_part_two_differential::<100>(0);
+        check_part_two_differential::<1>(0);
+        check_part_two_differential::<7>(6);
+        check_part_two_differential::<1000>(999);
+    }
+
+    #[test]
+    fn test_zeros_passed() {
+        let pointer = SafeNum::<100>::new(50);
+        assert_eq!(0, pointer.zeros_passed(Direction::Right, 49));
+        assert_eq!(1, pointer.zeros_passed(Direction::Right, 50));
+        assert_eq!(1, pointer.zeros_passed(Direction::Left, 50));
+        assert_eq!(2, pointer.zeros_passed(Direction::Left, 150));
+        let zero = SafeNum::<100>::new(0);
+        assert_eq!(0, zero.zeros_passed(Direction::Left, 99));
+        assert_eq!(1, zero.zeros_passed(Direction::Left, 100));
+        assert_eq!(1, zero.zeros_passed(Direction::Right, 100));
+
+        let input = format!("R{}", u64::MAX);
+        let actual = _part_two(input.as_bytes(), Dial::START).unwrap();
+        assert_eq!(184_467_440_737_095_516, actual);
+
+        let input = format!("R{}\nL{}", u128::MAX, u128::MAX);
+        let err = _part_two(input.as_bytes(), Dial::START).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_dial_sizes() {
+        let mut pointer = SafeNum::<1000>::new(998);
+        pointer = pointer + SafeNum::new(3);
+        assert_eq!(1, pointer.0);
+        pointer = pointer - SafeNum::new(2);
+        assert_eq!(999, pointer.0);
+
+        let big = SafeNum::<{ u64::MAX }>::new(u64::MAX - 1);
+        assert_eq!(2, (big + big + SafeNum::new(4)).0);
+
+        let input = "R5\nL10\nR10";
+        assert_eq!(
+            3,
+            _part_one(input.as_bytes(), SafeNum::<10>::new(5)).unwrap()
+        );
+        assert_eq!(
+            0,
+            _part_one(input.as_bytes(), SafeNum::<10>::new(0)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "L68\nL30\nR48";
+        let mut out = vec![];
+        let mut writer = TraceWriter::new(&mut out, TraceFormat::Csv, LineMap::default());
+        trace(Box::new(input.as_bytes()), &mut writer).unwrap();
+        writer.finish().unwrap();
+        let expected = "line,direction,magnitude,before,after,zeros_passed,landed_on_zero
+1,L,68,50,82,1,false
+2,L,30,82,52,0,false
+3,R,48,52,0,1,true
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+
+        let mut out = vec![];
+        let mut writer = TraceWriter::new(&mut out, TraceFormat::Json, LineMap::default());
+        trace(Box::new("R150".as_bytes()), &mut writer).unwrap();
+        writer.finish().unwrap();
+        let expected = r#"[
+  {"line": 1, "direction": "R", "magnitude": 150, "before": 50, "after": 0, "zeros_passed": 2, "landed_on_zero": true}
+]
+"#;
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn test_count_online() {
+        let mut reports = vec![];
+        count_online(TEST_INPUT.as_bytes(), 4, |count| {
+            reports.push((count.rotations, count.part_one, count.part_two));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec![(4, 1, 2), (8, 3, 5), (10, 3, 6)], reports);
+
+        let mut reports = vec![];
+        count_online("L50\nR100\n".as_bytes(), 2, |count| {
+            reports.push((count.rotations, count.part_one, count.part_two));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec![(2, 2, 2)], reports);
+    }
+
+    #[test]
+    fn test_lock() {
+        // The puzzle's input on a lock with just the puzzle's dial gives the same answers
+        let wheels = ["100@50".parse().unwrap()];
+        let report = simulate_lock(TEST_INPUT.as_bytes(), &wheels).unwrap();
+        assert_eq!(
+            (10, 3, 6),
+            (report.steps, report.all_zero, report.wheels[0].passed)
+        );
+
+        let wheels: Vec<Wheel> = ["10@5", "4@1"].map(|w| w.parse().unwrap()).to_vec();
+        let input = "1:R5\n2:L1\n2:R8\n1:L20\nR10\n2:R2";
+        let report = simulate_lock(input.as_bytes(), &wheels).unwrap();
+        let expected = LockReport {
+            wheels: vec![
+                WheelReport {
+                    wheel: Wheel { size: 10, start: 5 },
+                    rotations: 3,
+                    landed: 3,
+                    passed: 4,
+                },
+                WheelReport {
+                    wheel: Wheel { size: 4, start: 1 },
+                    rotations: 3,
+                    landed: 2,
+                    passed: 3,
+                },
+            ],
+            steps: 6,
+            all_zero: 4,
+        };
+        assert_eq!(expected, report);
+
+        let err = simulate_lock("1:R5\n3:L1".as_bytes(), &wheels).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 1, "3"), (err.line, err.column(), err.text()));
+        let err = simulate_lock("2:Lx".as_bytes(), &wheels).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((1, 4, "x"), (err.line, err.column(), err.text()));
+
+        assert!("0@0".parse::<Wheel>().is_err());
+        assert!("10".parse::<Wheel>().is_err());
+        assert!("10@12".parse::<Wheel>().is_err());
+        assert!("10@10".parse::<Wheel>().is_err());
+        assert_eq!(Wheel { size: 10, start: 9 }, "10@9".parse().unwrap());
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(1, 300);
+    }
 }
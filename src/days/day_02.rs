@@ -1,109 +1,1180 @@
-use std::io::BufRead;
+use std::{
+    io::{BufRead, Write},
+    ops::RangeInclusive,
+};
 
 use anyhow::Context;
+use clap::ValueEnum;
 use itertools::Itertools;
 
-use crate::puzzle::Puzzle;
+use crate::{
+    inspect::{Stat, summarize},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::{Implementation, Puzzle},
+    rng::Rng,
+    validate::{Violation, check_range},
+};
 
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![
+            Implementation {
+                name: "serial",
+                part: 1,
+                solver: Box::new(|input| Ok(Box::new(_part_one(&read_line(input)?, SERIAL)?))),
+            },
+            Implementation {
+                name: "serial",
+                part: 2,
+                solver: Box::new(|input| Ok(Box::new(_part_two(&read_line(input)?, SERIAL)?))),
+            },
+            Implementation {
+                name: "brute",
+                part: 1,
+                solver: Box::new(|input| {
+                    Ok(Box::new(_part_one_brute(
+                        &read_line(input)?,
+                        IdOptions::default(),
+                    )?))
+                }),
+            },
+            Implementation {
+                name: "brute",
+                part: 2,
+                solver: Box::new(|input| {
+                    Ok(Box::new(_part_two_brute(
+                        &read_line(input)?,
+                        IdOptions::default(),
+                    )?))
+                }),
+            },
+        ],
+        normalization: Normalization::default(),
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: Some(Box::new(lint)),
+        trace: None,
     }
 }
 
 fn part_one(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    // This day's puzzle input is a single line
-    let input: String = input.lines().collect::<Result<_, _>>()?;
-    let ans = _part_one(&input)?;
+    let ans = _part_one(&read_line(input)?, IdOptions::default())?;
     Ok(Box::new(ans))
 }
 
 fn part_two(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    // This day's puzzle input is a single line
-    let input: String = input.lines().collect::<Result<_, _>>()?;
-    let ans = _part_two(&input)?;
+    let ans = _part_two(&read_line(input)?, IdOptions::default())?;
     Ok(Box::new(ans))
 }
 
-fn _part_one(input: &str) -> anyhow::Result<usize> {
-    let mut invalid_sum = 0;
+/// Read the puzzle input, which is a single line.
+fn read_line(input: Box<dyn BufRead>) -> std::io::Result<String> {
+    input.lines().collect()
+}
 
-    let ranges = input.split(',');
-    for range in ranges {
-        let (min, max) = range
-            .split_once('-')
-            .context("Malformed range, missing '-' separator")?;
+/// How to read the IDs, for analysing lists of ranges other than the puzzle's own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IdOptions {
+    /// Radix that the IDs are written in, from 2 to 36, where the repeated blocks are blocks of
+    /// digits in that radix
+    pub radix: u32,
+    /// How IDs that are in more than one range are counted
+    pub counting: Counting,
+    /// Number of threads that the ranges are split across, where 1 processes them serially
+    pub threads: usize,
+}
 
-        let min: usize = min.parse()?;
-        let max: usize = max.parse()?;
+impl Default for IdOptions {
+    fn default() -> Self {
+        Self {
+            radix: 10,
+            counting: Counting::Distinct,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
 
-        for num in min..=max {
-            let num_str = num.to_string();
-            if num_str.len() % 2 != 0 {
-                // Can't evenly split a number that doesn't have an even number of digits
-                continue;
+/// Options for working through the puzzle's own ranges one after another on the current thread.
+const SERIAL: IdOptions = IdOptions {
+    radix: 10,
+    counting: Counting::Distinct,
+    threads: 1,
+};
+
+/// How to count IDs which are in more than one of the ranges, since they overlap.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Counting {
+    /// Count an ID once for every range that it's in
+    Occurrences,
+    /// Count each ID once, however many ranges it's in
+    Distinct,
+}
+
+/// A range which shares some IDs with another range of the input.
+#[derive(Debug, PartialEq)]
+struct Overlap {
+    /// 0-based index of the range in the input
+    range: usize,
+    /// 0-based index of the earlier range that it overlaps
+    other: usize,
+    /// IDs which are in both ranges
+    shared: RangeInclusive<u128>,
+}
+
+/// Sort the `ranges` and merge those that overlap or touch, along with every range which
+/// overlaps another one.
+///
+/// Each overlapping range is paired with whichever range seen before it, by start, reaches the
+/// furthest, so it's reported once even if it overlaps several.
+fn merge_ranges(ranges: &[RangeInclusive<u128>]) -> (Vec<RangeInclusive<u128>>, Vec<Overlap>) {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|idx| (*ranges[*idx].start(), *ranges[*idx].end(), *idx));
+
+    let mut merged: Vec<RangeInclusive<u128>> = vec![];
+    let mut overlaps = vec![];
+    // The range which reaches the furthest so far
+    let mut furthest: Option<usize> = None;
+    for idx in order {
+        let range = &ranges[idx];
+        if let Some(other) = furthest
+            && range.start() <= ranges[other].end()
+        {
+            overlaps.push(Overlap {
+                range: idx.max(other),
+                other: idx.min(other),
+                shared: *range.start()..=*range.end().min(ranges[other].end()),
+            });
+        }
+        if furthest.is_none_or(|other| range.end() > ranges[other].end()) {
+            furthest = Some(idx);
+        }
+
+        match merged.last_mut() {
+            Some(last) if range.start().saturating_sub(1) <= *last.end() => {
+                *last = *last.start()..=*range.end().max(last.end());
             }
-            let (front, back) = num_str.split_at(num_str.len() / 2);
-            if front == back {
-                invalid_sum += num;
+            _ => merged.push(range.clone()),
+        }
+    }
+
+    overlaps.sort_by_key(|overlap| (overlap.range, overlap.other));
+    (merged, overlaps)
+}
+
+/// The ranges of IDs in an input, along with those that overlap.
+pub struct IdRanges {
+    /// Every range, in the order of the input
+    ranges: Vec<RangeInclusive<u128>>,
+    /// The ranges merged where they overlap or touch, in ascending order
+    merged: Vec<RangeInclusive<u128>>,
+    /// Every range which shares IDs with an earlier one
+    overlaps: Vec<Overlap>,
+}
+
+impl IdRanges {
+    /// Read the ranges from the `input`, with bounds written in the given `radix`.
+    pub fn read(input: Box<dyn BufRead>, radix: u32) -> anyhow::Result<Self> {
+        Ok(Self::parse(&read_line(input)?, radix)?)
+    }
+
+    fn parse(input: &str, radix: u32) -> Result<Self, ParseError> {
+        let ranges = parse_ranges(input, radix)?;
+        let (merged, overlaps) = merge_ranges(&ranges);
+        Ok(Self {
+            ranges,
+            merged,
+            overlaps,
+        })
+    }
+
+    /// The ranges to sum the invalid IDs of, which are merged when counting distinct IDs.
+    fn to_sum(&self, counting: Counting) -> &[RangeInclusive<u128>] {
+        match counting {
+            Counting::Occurrences => &self.ranges,
+            Counting::Distinct => &self.merged,
+        }
+    }
+
+    /// Describe every range which overlaps or duplicates another, and how their shared IDs are
+    /// counted, to warn about them once before the IDs are summed.
+    pub fn overlap_warnings(&self, options: IdOptions) -> Vec<String> {
+        let show = |idx: usize| {
+            format!(
+                "range {} ({}-{})",
+                idx + 1,
+                to_radix(*self.ranges[idx].start(), options.radix),
+                to_radix(*self.ranges[idx].end(), options.radix)
+            )
+        };
+        let counted = match options.counting {
+            Counting::Occurrences => "counted in both",
+            Counting::Distinct => "counted once",
+        };
+
+        self.overlaps
+            .iter()
+            .map(
+                |Overlap {
+                     range,
+                     other,
+                     shared,
+                 }| {
+                    if self.ranges[*range] == self.ranges[*other] {
+                        format!(
+                            "{} duplicates {}, its IDs are {counted}",
+                            show(*range),
+                            show(*other)
+                        )
+                    } else {
+                        format!(
+                            "{} overlaps {} on {}-{}, those IDs are {counted}",
+                            show(*range),
+                            show(*other),
+                            to_radix(*shared.start(), options.radix),
+                            to_radix(*shared.end(), options.radix)
+                        )
+                    }
+                },
+            )
+            .collect()
+    }
+}
+
+/// Count and sum the invalid IDs for `part` in the `ranges`, see [`IdOptions`] for how they're
+/// counted.
+pub fn find_invalid_ids(
+    ranges: &IdRanges,
+    part: u8,
+    options: IdOptions,
+) -> anyhow::Result<Invalid> {
+    total_invalid(ranges.to_sum(options.counting), part_repeats(part), options)
+}
+
+/// Warn about any ranges of the puzzle's own input which overlap.
+fn lint(input: Box<dyn BufRead>) -> anyhow::Result<Vec<String>> {
+    let options = IdOptions::default();
+    Ok(IdRanges::read(input, options.radix)?.overlap_warnings(options))
+}
+
+/// Parse the comma-separated `{min}-{max}` ranges of IDs from the puzzle input, with bounds
+/// written in the given `radix`.
+fn parse_ranges(input: &str, radix: u32) -> Result<Vec<RangeInclusive<u128>>, ParseError> {
+    // This day's puzzle input is a single line
+    let parse_bound = |bound: &str| {
+        u128::from_str_radix(bound, radix)
+            .map_err(|_| ParseError::at("Could not parse valid range bound int", 1, input, bound))
+    };
+
+    input
+        .split(',')
+        .map(|range| {
+            let (min, max) = range.split_once('-').ok_or_else(|| {
+                ParseError::at("Malformed range, missing '-' separator", 1, input, range)
+            })?;
+
+            let (min, max) = (parse_bound(min)?, parse_bound(max)?);
+            if min > max {
+                return Err(ParseError::at(
+                    "Range start should not be greater than its end",
+                    1,
+                    input,
+                    range,
+                ));
+            }
+            Ok(min..=max)
+        })
+        .collect()
+}
+
+/// Sum the IDs which are a block of digits repeated exactly twice.
+fn _part_one(input: &str, options: IdOptions) -> anyhow::Result<u128> {
+    let ranges = IdRanges::parse(input, options.radix)?;
+    Ok(total_invalid(ranges.to_sum(options.counting), Repeats::Twice, options)?.sum)
+}
+
+/// Sum the IDs which are a block of digits repeated at least twice.
+fn _part_two(input: &str, options: IdOptions) -> anyhow::Result<u128> {
+    let ranges = IdRanges::parse(input, options.radix)?;
+    Ok(total_invalid(
+        ranges.to_sum(options.counting),
+        Repeats::AtLeastTwice,
+        options,
+    )?
+    .sum)
+}
+
+/// How many times the block of digits of an invalid ID should be repeated for `part`.
+fn part_repeats(part: u8) -> Repeats {
+    match part {
+        1 => Repeats::Twice,
+        _ => Repeats::AtLeastTwice,
+    }
+}
+
+/// How many times the block of digits of an invalid ID should be repeated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Repeats {
+    /// As in part one
+    Twice,
+    /// As in part two
+    AtLeastTwice,
+}
+
+/// The number and sum of the invalid IDs within some ranges.
+#[derive(Debug, Default, PartialEq)]
+pub struct Invalid {
+    pub count: u128,
+    pub sum: u128,
+}
+
+impl Invalid {
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            count: self.count.checked_add(other.count)?,
+            sum: self.sum.checked_add(other.sum)?,
+        })
+    }
+}
+
+/// Count and sum the invalid IDs in all of the `ranges`.
+fn total_invalid(
+    ranges: &[RangeInclusive<u128>],
+    repeats: Repeats,
+    options: IdOptions,
+) -> anyhow::Result<Invalid> {
+    sum_ranges(ranges, options.threads, |range| {
+        invalid_ids(range, repeats, options.radix).context(OVERFLOW_MESSAGE)
+    })
+}
+
+/// Add up what `count` finds in each of the `ranges`, which are split across up to `threads`
+/// scoped threads.
+///
+/// Since each range is independent, the total is the same however the ranges are split, but it's
+/// worked out in order on the current thread if there's only one thread to use.
+fn sum_ranges(
+    ranges: &[RangeInclusive<u128>],
+    threads: usize,
+    count: impl Fn(&RangeInclusive<u128>) -> anyhow::Result<Invalid> + Sync,
+) -> anyhow::Result<Invalid> {
+    let add_up = |group: &[&RangeInclusive<u128>]| {
+        group.iter().try_fold(Invalid::default(), |total, range| {
+            total.checked_add(&count(range)?).context(OVERFLOW_MESSAGE)
+        })
+    };
+    if threads <= 1 || ranges.len() <= 1 {
+        return add_up(&ranges.iter().collect_vec());
+    }
+
+    let groups = balance_by_width(ranges, threads);
+    let subtotals = std::thread::scope(|scope| {
+        let handles = groups
+            .iter()
+            .map(|group| scope.spawn(|| add_up(group)))
+            .collect_vec();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect_vec()
+    });
+    subtotals
+        .into_iter()
+        .try_fold(Invalid::default(), |total, subtotal| {
+            total.checked_add(&subtotal?).context(OVERFLOW_MESSAGE)
+        })
+}
+
+/// Split the `ranges` into at most `groups` non-empty groups with about the same total width,
+/// since a wide range can take far longer than many narrow ones.
+///
+/// The widest ranges are placed first, each into whichever group is narrowest so far.
+fn balance_by_width(
+    ranges: &[RangeInclusive<u128>],
+    groups: usize,
+) -> Vec<Vec<&RangeInclusive<u128>>> {
+    let width = |range: &RangeInclusive<u128>| (range.end() - range.start()).saturating_add(1);
+    let mut balanced: Vec<(u128, Vec<&RangeInclusive<u128>>)> =
+        vec![(0, vec![]); groups.min(ranges.len())];
+    for range in ranges
+        .iter()
+        .sorted_by_key(|range| std::cmp::Reverse(width(range)))
+    {
+        let (total, group) = balanced
+            .iter_mut()
+            .min_by_key(|(total, _)| *total)
+            .expect("there should be at least one group");
+        *total = total.saturating_add(width(range));
+        group.push(range);
+    }
+    balanced.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Why summing invalid IDs failed, shared with the commands that add up subtotals themselves.
+pub const OVERFLOW_MESSAGE: &str = "Sum of invalid IDs is too large for a u128";
+
+/// Count and sum the invalid IDs in `range`, with digits in the given `radix`, without visiting
+/// each of its IDs. Returns `None` if the sum is too large for a `u128`.
+///
+/// An ID of `len` digits made of a block of `k` digits repeated `m` times is `block * R`, where
+/// `R = radix^(k(m-1)) + ... + radix^k + 1`. So for each possible digit count, the blocks which
+/// land in the range form a run of consecutive numbers, which is summed in closed form. An ID
+/// may be made of repeats in several ways, ie `111111` is `1`, `11` and `111` repeated, so for
+/// part two the repeat counts are combined by inclusion-exclusion over the prime factors of
+/// `len`.
+fn invalid_ids(range: &RangeInclusive<u128>, repeats: Repeats, radix: u32) -> Option<Invalid> {
+    let mut invalid = Invalid::default();
+
+    for (len, lo, hi) in by_digit_ct(range, radix) {
+        match repeats {
+            Repeats::Twice if len.is_multiple_of(2) => {
+                invalid = invalid.checked_add(&repeated_ids(lo, hi, len, len / 2, radix)?)?;
+            }
+            Repeats::Twice => {}
+            Repeats::AtLeastTwice => {
+                // An ID repeats a block `m` times exactly when it repeats one `p` times for some
+                // prime `p` dividing `m`, so only the IDs for prime repeat counts (and their
+                // overlaps) need counting. Whatever is removed was added before, so the total
+                // added is the larger of the two.
+                let primes = prime_factors(len);
+                let (mut added, mut removed) = (Invalid::default(), Invalid::default());
+                for subset in 1..1u32 << primes.len() {
+                    let product: u32 = (0..primes.len())
+                        .filter(|idx| subset & (1 << idx) != 0)
+                        .map(|idx| primes[idx])
+                        .product();
+                    let ids = repeated_ids(lo, hi, len, len / product, radix)?;
+                    if subset.count_ones() % 2 == 1 {
+                        added = added.checked_add(&ids)?;
+                    } else {
+                        removed = removed.checked_add(&ids)?;
+                    }
+                }
+                invalid = invalid.checked_add(&Invalid {
+                    count: added.count - removed.count,
+                    sum: added.sum - removed.sum,
+                })?;
             }
         }
     }
 
-    Ok(invalid_sum)
+    Some(invalid)
+}
+
+/// Split the `range` into the parts `(len, lo, hi)` where its IDs have exactly `len` digits in
+/// the `radix`, skipping those with a single digit which can't be repeated.
+fn by_digit_ct(
+    range: &RangeInclusive<u128>,
+    radix: u32,
+) -> impl Iterator<Item = (u32, u128, u128)> {
+    let (start, end) = (*range.start(), *range.end());
+    (digit_ct(start, radix).max(2)..=digit_ct(end, radix)).filter_map(move |len| {
+        // IDs of `len` digits always start within a u128, but may not end within one
+        let lo = start.max(radix_pow(radix, len - 1)?);
+        let hi = radix_pow(radix, len).map_or(end, |pow| end.min(pow - 1));
+        (lo <= hi).then_some((len, lo, hi))
+    })
+}
+
+/// Find the blocks of `block_len` digits in the `radix` which, repeated to `len` digits, make
+/// an ID in `lo..=hi`, along with the number that each block is multiplied by to repeat it.
+///
+/// Returns `None` if even the smallest ID of this shape wouldn't fit a `u128`, in which case
+/// there are no such IDs in the range.
+fn repeated_blocks(
+    lo: u128,
+    hi: u128,
+    len: u32,
+    block_len: u32,
+    radix: u32,
+) -> Option<(u128, RangeInclusive<u128>)> {
+    let repeat = (0..len / block_len)
+        .map(|idx| radix_pow(radix, idx * block_len))
+        .try_fold(0u128, |acc, pow| acc.checked_add(pow?))?;
+    let first = lo.div_ceil(repeat).max(radix_pow(radix, block_len - 1)?);
+    let last = radix_pow(radix, block_len).map_or(hi / repeat, |pow| (hi / repeat).min(pow - 1));
+    Some((repeat, first..=last))
+}
+
+/// Count and sum the IDs in `lo..=hi`, which all have `len` digits in the `radix`, that are
+/// made of a block of `block_len` digits repeated. Returns `None` if the sum is too large for a
+/// `u128`.
+fn repeated_ids(lo: u128, hi: u128, len: u32, block_len: u32, radix: u32) -> Option<Invalid> {
+    let Some((repeat, blocks)) = repeated_blocks(lo, hi, len, block_len, radix) else {
+        return Some(Invalid::default());
+    };
+    let (first, last) = (*blocks.start(), *blocks.end());
+    if first > last {
+        return Some(Invalid::default());
+    }
+
+    let count = last - first + 1;
+    // One of the two factors is even, so halve that one before multiplying
+    let block_sum = if count.is_multiple_of(2) {
+        (count / 2).checked_mul(first.checked_add(last)?)?
+    } else {
+        (first.checked_add(last)? / 2).checked_mul(count)?
+    };
+    Some(Invalid {
+        count,
+        sum: block_sum.checked_mul(repeat)?,
+    })
+}
+
+/// `radix` to the power of `exp`, or `None` if it doesn't fit a `u128`.
+fn radix_pow(radix: u32, exp: u32) -> Option<u128> {
+    u128::from(radix).checked_pow(exp)
+}
+
+/// Number of digits in `num`, written in the `radix`.
+fn digit_ct(num: u128, radix: u32) -> u32 {
+    num.checked_ilog(u128::from(radix)).unwrap_or_default() + 1
+}
+
+/// The distinct prime factors of `num`.
+fn prime_factors(mut num: u32) -> Vec<u32> {
+    let mut primes = vec![];
+    let mut factor = 2;
+    while num > 1 {
+        if num.is_multiple_of(factor) {
+            primes.push(factor);
+            while num.is_multiple_of(factor) {
+                num /= factor;
+            }
+        }
+        factor += 1;
+    }
+    primes
 }
 
-fn _part_two(input: &str) -> anyhow::Result<usize> {
-    let mut invalid_sum = 0;
+/// The digits of `num` written in the `radix`, most significant first.
+fn digits(mut num: u128, radix: u32) -> Vec<u8> {
+    let radix = u128::from(radix);
+    let mut digits = vec![];
+    loop {
+        digits.push((num % radix) as u8);
+        num /= radix;
+        if num == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
 
-    let ranges = input.split(',');
-    for range in ranges {
-        let (min, max) = range
-            .split_once('-')
-            .context("Malformed range, missing '-' separator")?;
+/// Sum the IDs which are a block of digits repeated exactly twice, by checking every ID.
+fn _part_one_brute(input: &str, options: IdOptions) -> anyhow::Result<u128> {
+    brute(input, options, |digits| {
+        let (front, back) = digits.split_at(digits.len() / 2);
+        // Can't evenly split a number that doesn't have an even number of digits
+        digits.len().is_multiple_of(2) && front == back
+    })
+}
 
-        let min: usize = min.parse()?;
-        let max: usize = max.parse()?;
+/// Sum the IDs which are a block of digits repeated at least twice, by checking every ID.
+fn _part_two_brute(input: &str, options: IdOptions) -> anyhow::Result<u128> {
+    brute(input, options, is_repeated)
+}
 
-        for num in min..=max {
-            let num_str = num.to_string();
-            // Iterate through all possible pattern lengths for the number
-            let chunk_sizes = (1..=num_str.len() / 2)
-                // Omit those that don't divide the number into whole groups
-                .filter(|chunk_size| num_str.len() % chunk_size == 0);
+/// Sum the IDs whose digits are `invalid`, by checking every ID in every range.
+fn brute(
+    input: &str,
+    options: IdOptions,
+    invalid: impl Fn(&[u8]) -> bool + Sync,
+) -> anyhow::Result<u128> {
+    let ranges = IdRanges::parse(input, options.radix)?;
+    let total = sum_ranges(ranges.to_sum(options.counting), options.threads, |range| {
+        range
+            .clone()
+            .filter(|num| invalid(&digits(*num, options.radix)))
+            .try_fold(Invalid::default(), |total, num| {
+                total.checked_add(&Invalid { count: 1, sum: num })
+            })
+            .context(OVERFLOW_MESSAGE)
+    })?;
+    Ok(total.sum)
+}
 
-            for size in chunk_sizes {
-                let chunks = num_str.chars().chunks(size);
-                let mut chunks = chunks.into_iter().map(Vec::from_iter);
-                if chunks.all_equal() {
-                    invalid_sum += num;
-                    break;
+/// Whether the `digits` are made of some block of them repeated at least twice.
+fn is_repeated(digits: &[u8]) -> bool {
+    // Iterate through all possible pattern lengths for the number
+    (1..=digits.len() / 2)
+        // Omit those that don't divide the number into whole groups
+        .filter(|chunk_size| digits.len().is_multiple_of(*chunk_size))
+        .any(|size| digits.chunks(size).all_equal())
+}
+
+/// An invalid ID, with the block of digits that it repeats.
+#[derive(Debug, PartialEq)]
+pub struct InvalidId {
+    pub id: u128,
+    /// The shortest block which repeats to make the ID, or its first half for part one
+    pub block: u128,
+    pub repeats: u32,
+    /// 0-based index of the earlier range which already counted the ID, when counting distinct
+    /// IDs, so that it's left out of this range's subtotal
+    pub counted_in: Option<usize>,
+}
+
+impl InvalidId {
+    /// Describe the ID as the repeats of its block, ie `824824824 = "824" x3`, with both
+    /// written in the `radix`.
+    pub fn describe(&self, radix: u32) -> String {
+        format!(
+            "{} = \"{}\" x{}",
+            to_radix(self.id, radix),
+            to_radix(self.block, radix),
+            self.repeats
+        )
+    }
+}
+
+/// The invalid IDs within a single range of the input.
+#[derive(Debug, PartialEq)]
+pub struct RangeReport {
+    pub range: RangeInclusive<u128>,
+    /// Every invalid ID in the range, in ascending order
+    pub ids: Vec<InvalidId>,
+    /// The range's part of the answer, which adds up the `ids` that no earlier range counted
+    pub subtotal: Invalid,
+}
+
+/// Like [`find_invalid_ids`], but list each invalid ID under the range of the input that it's
+/// in, with the subtotal of each range, in the order of the input.
+///
+/// When counting distinct IDs, an ID which an earlier range already has is still listed, but
+/// marked as already counted and left out of the subtotal, so that the subtotals add up to the
+/// answer. Every invalid ID is held in memory, so this is only for ranges without too many of
+/// them.
+pub fn itemize_invalid_ids(
+    ranges: &IdRanges,
+    part: u8,
+    options: IdOptions,
+) -> anyhow::Result<Vec<RangeReport>> {
+    let repeats = part_repeats(part);
+    let ranges = &ranges.ranges;
+    ranges
+        .iter()
+        .enumerate()
+        .map(|(idx, range)| {
+            let mut ids = list_invalid_ids(range, repeats, options.radix);
+            let mut subtotal = Invalid::default();
+            for id in &mut ids {
+                if options.counting == Counting::Distinct {
+                    id.counted_in = ranges[..idx]
+                        .iter()
+                        .position(|earlier| earlier.contains(&id.id));
+                }
+                if id.counted_in.is_none() {
+                    subtotal = subtotal
+                        .checked_add(&Invalid {
+                            count: 1,
+                            sum: id.id,
+                        })
+                        .context(OVERFLOW_MESSAGE)?;
+                }
+            }
+            Ok(RangeReport {
+                range: range.clone(),
+                ids,
+                subtotal,
+            })
+        })
+        .collect()
+}
+
+/// List every invalid ID in the `range`, by generating them from their blocks of digits.
+fn list_invalid_ids(range: &RangeInclusive<u128>, repeats: Repeats, radix: u32) -> Vec<InvalidId> {
+    let mut ids = vec![];
+    for (len, lo, hi) in by_digit_ct(range, radix) {
+        let block_lens: Vec<u32> = match repeats {
+            Repeats::Twice if len.is_multiple_of(2) => vec![len / 2],
+            Repeats::Twice => vec![],
+            Repeats::AtLeastTwice => (1..len).filter(|k| len.is_multiple_of(*k)).collect(),
+        };
+
+        let first_id = ids.len();
+        for block_len in block_lens {
+            let Some((repeat, blocks)) = repeated_blocks(lo, hi, len, block_len, radix) else {
+                continue;
+            };
+            for block in blocks {
+                // A block which is itself repeated makes an ID that a shorter block makes too
+                if repeats == Repeats::AtLeastTwice && is_repeated(&digits(block, radix)) {
+                    continue;
                 }
+                ids.push(InvalidId {
+                    id: block * repeat,
+                    block,
+                    repeats: len / block_len,
+                    counted_in: None,
+                });
             }
         }
+        ids[first_id..].sort_by_key(|id| id.id);
+    }
+    ids
+}
+
+/// Write `num` in the `radix`, with lowercase letters for digits past 9.
+pub fn to_radix(num: u128, radix: u32) -> String {
+    digits(num, radix)
+        .into_iter()
+        .map(|digit| char::from_digit(u32::from(digit), radix).unwrap_or('?'))
+        .collect()
+}
+
+/// The input should be a single line of comma-separated `{start}-{end}` ranges.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut lines = input.lines();
+
+    let Some(line) = lines.next().transpose()? else {
+        violations.push(Violation::new(
+            1,
+            1,
+            "empty input, expected a list of ranges",
+        ));
+        return Ok(violations);
+    };
+    let mut column = 1;
+    for range in line.split(',') {
+        check_range::<u128>(1, column, range, &mut violations);
+        column += range.chars().count() + 1;
+    }
+
+    for (idx, line) in lines.enumerate() {
+        line?;
+        violations.push(Violation::new(
+            idx + 2,
+            1,
+            "unexpected extra line, ranges should all be on the first line",
+        ));
     }
 
-    Ok(invalid_sum)
+    Ok(violations)
+}
+
+/// How wide the ranges are, how many digits their bounds have and how many overlap.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let input: String = input.lines().collect::<Result<_, _>>()?;
+    let ranges = parse_ranges(&input, IdOptions::default().radix)?;
+
+    let widths: Vec<usize> = ranges
+        .iter()
+        .map(|range| usize::try_from(range.end() - range.start()).unwrap_or(usize::MAX))
+        .map(|width| width.saturating_add(1))
+        .collect();
+    let digit_cts = ranges
+        .iter()
+        .flat_map(|range| [*range.start(), *range.end()])
+        .map(|bound| bound.to_string().len());
+    let mixed_digit_cts = ranges
+        .iter()
+        .filter(|range| range.start().to_string().len() != range.end().to_string().len())
+        .count();
+
+    Ok(vec![
+        Stat::new("Ranges", ranges.len()),
+        Stat::new(
+            "IDs covered",
+            widths
+                .iter()
+                .fold(0, |acc: usize, w| acc.saturating_add(*w)),
+        ),
+        Stat::new("Range width", summarize(widths)),
+        Stat::new("Digits per bound", summarize(digit_cts)),
+        Stat::new("Ranges spanning digit counts", mixed_digit_cts),
+        Stat::new("Overlapping ranges", merge_ranges(&ranges).1.len()),
+    ])
+}
+
+/// `size` ranges, with bounds of up to ten digits that are at most 100,000 IDs apart.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut ranges = (0..size).map(|_| {
+        let digits = rng.range(1..=10) as u32;
+        let min = rng.range(10usize.pow(digits - 1)..=10usize.pow(digits) - 1);
+        let max = min + rng.range(0..=100_000);
+        format!("{min}-{max}")
+    });
+    writeln!(out, "{}", ranges.join(","))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
+    use crate::{differential, fuzz};
 
     const INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
+    /// Options for the puzzle's own decimal IDs
+    const DECIMAL: IdOptions = IdOptions {
+        radix: 10,
+        counting: Counting::Distinct,
+        threads: 4,
+    };
+
     #[test]
     fn test_part_one() {
         let expected = 1227775554;
-        let actual = _part_one(INPUT).unwrap();
+        let actual = _part_one(INPUT, DECIMAL).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_part_two() {
         let expected = 4174379265;
-        let actual = _part_two(INPUT).unwrap();
+        let actual = _part_two(INPUT, DECIMAL).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(1, 7, "malformed range \"95\", expected {start}-{end}"),
+            Violation::new(1, 10, "range start 22 is greater than its end 11"),
+        ];
+        let actual = validate(Box::new("11-22,95,22-11".as_bytes())).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(2), 200, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_ranges("11-22,95115,998-1012", 10).unwrap_err();
+        assert_eq!((1, 7, "95115"), (err.line, err.column(), err.text()));
+
+        let err = parse_ranges("11-22,95-1x5", 10).unwrap_err();
+        assert_eq!((1, 10, "1x5"), (err.line, err.column(), err.text()));
+
+        let err = parse_ranges("101-121", 2).unwrap_err();
+        assert_eq!((1, 5, "121"), (err.line, err.column(), err.text()));
+    }
+
+    /// Random ranges written in the `radix`, which often cross from one digit count to the next.
+    fn generate_ranges(rng: &mut Rng, radix: u32) -> String {
+        let mut ranges = (0..rng.range(1..=5)).map(|_| {
+            let digits = rng.range(1..=7) as u32;
+            let pow = (radix as usize).pow(digits);
+            let start = if rng.chance(0.3) {
+                pow.saturating_sub(rng.range(1..=50))
+            } else {
+                rng.range(0..=pow)
+            };
+            let end = start + rng.range(0..=2_000);
+            format!(
+                "{}-{}",
+                to_radix(start as u128, radix),
+                to_radix(end as u128, radix)
+            )
+        });
+        ranges.join(",")
+    }
+
+    #[test]
+    fn test_part_one_differential() {
+        for radix in [10, 2, 16, 36] {
+            let options = IdOptions { radix, ..DECIMAL };
+            differential::run(
+                150,
+                |rng| generate_ranges(rng, radix),
+                |input| _part_one(input, options).unwrap(),
+                |input| _part_one_brute(input, options).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_part_two_differential() {
+        for radix in [10, 2, 16, 36] {
+            let options = IdOptions { radix, ..DECIMAL };
+            differential::run(
+                150,
+                |rng| generate_ranges(rng, radix),
+                |input| _part_two(input, options).unwrap(),
+                |input| _part_two_brute(input, options).unwrap(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        // Every 2-digit ID with both digits the same, and every 4-digit one with a repeated pair
+        let invalid = invalid_ids(&(0..=9999), Repeats::Twice, 10).unwrap();
+        assert_eq!(9 + 90, invalid.count);
+        let invalid = invalid_ids(&(0..=9999), Repeats::AtLeastTwice, 10).unwrap();
+        // 1111 to 9999 repeat both a single digit and a pair, but are only counted once
+        assert_eq!(9 + 9 + 90, invalid.count);
+
+        // A range of every 20-digit ID, far too many to visit one at a time
+        let range = 10u128.pow(19)..=u128::from(u64::MAX);
+        let invalid = invalid_ids(&range, Repeats::Twice, 10).unwrap();
+        assert_eq!(
+            (u128::from(u64::MAX) / (10u128.pow(10) + 1)) - 10u128.pow(9) + 1,
+            invalid.count
+        );
+        assert!(_part_one(&format!("0-{}", u128::MAX), DECIMAL).is_err());
+    }
+
+    #[test]
+    fn test_radix() {
+        let hex = IdOptions {
+            radix: 16,
+            ..DECIMAL
+        };
+        // 0x11, 0x22, ..., 0xff
+        assert_eq!(
+            0x11 * (1..=15).sum::<u128>(),
+            _part_one("a-ff", hex).unwrap()
+        );
+        assert_eq!(0xabab + 0xacac, _part_one("ABAB-acac", hex).unwrap());
+
+        let binary = IdOptions {
+            radix: 2,
+            ..DECIMAL
+        };
+        assert_eq!(0b1010 + 0b1111, _part_one("1010-1111", binary).unwrap());
+        assert_eq!(
+            0b111 + 0b1010 + 0b1111,
+            _part_two("111-1111", binary).unwrap()
+        );
+
+        // Every bit set is both the largest u128 and an invalid ID
+        let max = "1".repeat(128);
+        let input = format!("{max}-{max}");
+        assert_eq!(u128::MAX, _part_one(&input, binary).unwrap());
+        assert_eq!(u128::MAX, _part_two(&input, binary).unwrap());
+        let max = u128::MAX.to_string();
+        assert_eq!(
+            _part_two_brute(&format!("{}-{max}", u128::MAX - 1000), DECIMAL).unwrap(),
+            _part_two(&format!("{}-{max}", u128::MAX - 1000), DECIMAL).unwrap()
+        );
+    }
+
+    fn itemize(input: &str, part: u8, options: IdOptions) -> Vec<RangeReport> {
+        let ranges = IdRanges::parse(input, options.radix).unwrap();
+        itemize_invalid_ids(&ranges, part, options).unwrap()
+    }
+
+    #[test]
+    fn test_itemize() {
+        let ranges = itemize(INPUT, 2, DECIMAL);
+        let described: Vec<Vec<String>> = ranges
+            .iter()
+            .map(|range| range.ids.iter().map(|id| id.describe(10)).collect())
+            .collect();
+        assert_eq!(vec!["11 = \"1\" x2", "22 = \"2\" x2"], described[0]);
+        assert_eq!(vec!["99 = \"9\" x2", "111 = \"1\" x3"], described[1]);
+        assert_eq!(vec!["824824824 = \"824\" x3"], described[9]);
+        assert_eq!(vec!["2121212121 = \"21\" x5"], described[10]);
+        assert_eq!(Invalid { count: 2, sum: 210 }, ranges[1].subtotal);
+
+        // Part one only splits IDs in half, even when a shorter block repeats
+        let ranges = itemize("1111-1111", 1, DECIMAL);
+        assert_eq!("1111 = \"11\" x2", ranges[0].ids[0].describe(10));
+        let hex = IdOptions {
+            radix: 16,
+            ..DECIMAL
+        };
+        assert_eq!(
+            "ff = \"f\" x2",
+            itemize("a-ff", 1, hex)[0].ids[14].describe(16)
+        );
+    }
+
+    #[test]
+    fn test_itemize_overlaps() {
+        // Ranges are listed in the order of the input, even when they overlap
+        let ranges = itemize("11-22,20-33,10-12", 1, DECIMAL);
+        let listed: Vec<_> = ranges
+            .iter()
+            .map(|range| {
+                let ids: Vec<_> = range.ids.iter().map(|id| (id.id, id.counted_in)).collect();
+                (range.range.clone(), ids, range.subtotal.sum)
+            })
+            .collect();
+        let expected = vec![
+            (11..=22, vec![(11, None), (22, None)], 33),
+            (20..=33, vec![(22, Some(0)), (33, None)], 33),
+            (10..=12, vec![(11, Some(0))], 0),
+        ];
+        assert_eq!(expected, listed);
+
+        let occurrences = IdOptions {
+            counting: Counting::Occurrences,
+            ..DECIMAL
+        };
+        let ranges = itemize("11-22,20-33,10-12", 1, occurrences);
+        let subtotals: Vec<_> = ranges.iter().map(|range| range.subtotal.sum).collect();
+        assert_eq!(vec![33, 55, 11], subtotals);
+        assert!(
+            ranges
+                .iter()
+                .flat_map(|range| &range.ids)
+                .all(|id| id.counted_in.is_none())
+        );
+    }
+
+    #[test]
+    fn test_itemize_subtotals() {
+        for (radix, counting) in [10, 2, 16]
+            .into_iter()
+            .cartesian_product([Counting::Distinct, Counting::Occurrences])
+        {
+            let options = IdOptions {
+                radix,
+                counting,
+                ..DECIMAL
+            };
+            differential::run(
+                100,
+                |rng| generate_ranges(rng, radix),
+                |input| {
+                    let listed: Vec<Invalid> = itemize(input, 2, options)
+                        .iter()
+                        .map(|range| {
+                            let counted = range.ids.iter().filter(|id| id.counted_in.is_none());
+                            Invalid {
+                                count: counted.clone().count() as u128,
+                                sum: counted.map(|id| id.id).sum(),
+                            }
+                        })
+                        .collect();
+                    let total = listed.iter().fold(Invalid::default(), |total, subtotal| {
+                        total.checked_add(subtotal).unwrap()
+                    });
+                    (listed, total)
+                },
+                |input| {
+                    let ranges = IdRanges::parse(input, radix).unwrap();
+                    let subtotals = itemize_invalid_ids(&ranges, 2, options)
+                        .unwrap()
+                        .into_iter()
+                        .map(|range| range.subtotal)
+                        .collect();
+                    (subtotals, find_invalid_ids(&ranges, 2, options).unwrap())
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let ranges = [10..=30, 20..=40, 10..=30, 50..=60, 55..=58, 61..=70];
+        let (merged, overlaps) = merge_ranges(&ranges);
+        assert_eq!(vec![10..=40, 50..=70], merged);
+        let expected = vec![
+            Overlap {
+                range: 1,
+                other: 0,
+                shared: 20..=30,
+            },
+            Overlap {
+                range: 2,
+                other: 0,
+                shared: 10..=30,
+            },
+            Overlap {
+                range: 4,
+                other: 3,
+                shared: 55..=58,
+            },
+        ];
+        assert_eq!(expected, overlaps);
+
+        let input = "11-22,11-22,20-33";
+        let warnings = IdRanges::parse(input, 10)
+            .unwrap()
+            .overlap_warnings(DECIMAL);
+        let expected = vec![
+            "range 2 (11-22) duplicates range 1 (11-22), its IDs are counted once",
+            "range 3 (20-33) overlaps range 1 (11-22) on 20-22, those IDs are counted once",
+        ];
+        assert_eq!(expected, warnings);
+
+        assert_eq!(11 + 22 + 33, _part_one(input, DECIMAL).unwrap());
+        let occurrences = IdOptions {
+            counting: Counting::Occurrences,
+            ..DECIMAL
+        };
+        assert_eq!(
+            2 * (11 + 22) + 22 + 33,
+            _part_one(input, occurrences).unwrap()
+        );
+        assert_eq!(
+            _part_two_brute(input, occurrences).unwrap(),
+            _part_two(input, occurrences).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parallel_differential() {
+        for threads in [2, 3, 8] {
+            let options = IdOptions { threads, ..DECIMAL };
+            let serial = IdOptions {
+                threads: 1,
+                ..options
+            };
+            differential::run(
+                100,
+                |rng| generate_ranges(rng, 10),
+                |input| {
+                    let one = _part_one(input, options).unwrap();
+                    (one, _part_two(input, options).unwrap())
+                },
+                |input| {
+                    let one = _part_one(input, serial).unwrap();
+                    (one, _part_two(input, serial).unwrap())
+                },
+            );
+        }
+
+        let huge = format!("1-{}", u128::MAX);
+        for threads in [1, 4] {
+            let err = _part_two(&huge, IdOptions { threads, ..DECIMAL }).unwrap_err();
+            assert_eq!(OVERFLOW_MESSAGE, err.to_string());
+        }
+    }
+
+    #[test]
+    fn test_balance_by_width() {
+        let ranges = [1..=100, 1..=10, 1..=40, 1..=50, 1..=5, 1..=5];
+        let widths = |groups: Vec<Vec<&RangeInclusive<u128>>>| {
+            groups
+                .iter()
+                .map(|group| group.iter().map(|r| r.end() - r.start() + 1).collect_vec())
+                .collect_vec()
+        };
+        assert_eq!(
+            vec![vec![100, 5], vec![50, 40, 10, 5]],
+            widths(balance_by_width(&ranges, 2))
+        );
+        assert_eq!(
+            vec![vec![100], vec![50, 5], vec![40, 10, 5]],
+            widths(balance_by_width(&ranges, 3))
+        );
+        assert_eq!(6, balance_by_width(&ranges, 16).len());
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(2, 300);
+    }
 }
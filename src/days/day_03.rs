@@ -1,13 +1,42 @@
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 
+use anyhow::Context;
 use itertools::Itertools;
 
-use crate::puzzle::Puzzle;
+use crate::{
+    inspect::{Stat, summarize},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::{Implementation, Puzzle},
+    rng::Rng,
+    validate::{DIGITS, Violation, check_chars},
+};
+
+/// Number of batteries that are turned on in each bank for part two
+const BATTERIES_ON: usize = 12;
 
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![
+            Implementation {
+                name: "window",
+                part: 1,
+                solver: Box::new(|input| Ok(Box::new(_total_joltage_window(input, 2)?))),
+            },
+            Implementation {
+                name: "window",
+                part: 2,
+                solver: Box::new(|input| Ok(Box::new(_total_joltage_window(input, BATTERIES_ON)?))),
+            },
+        ],
+        normalization: Normalization::default(),
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: None,
     }
 }
 
@@ -22,26 +51,21 @@ fn part_two(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
 }
 
 fn _part_one(input: impl BufRead) -> anyhow::Result<u32> {
-    input.lines().try_fold(0, |acc, line| {
-        let line = line?;
+    input.lines().enumerate().try_fold(0, |acc, (idx, line)| {
+        let digits = parse_bank(idx + 1, &line?)?;
 
         // (idx, el)
         let mut left = (0, 0);
-
-        let digits = line.chars().filter_map(|c| c.to_digit(10));
-        let digits: Vec<_> = digits
-            .enumerate()
-            .map(|(idx, el)| {
-                if el > left.1 && idx != line.len() - 1 {
-                    left = (idx, el);
-                }
-                el
-            })
-            .collect();
-        let right = *digits
-            .get(left.0 + 1..line.len())
-            .and_then(|digits| digits.iter().max())
-            .unwrap();
+        for (pos, el) in digits.iter().enumerate() {
+            if *el > left.1 && pos != digits.len() - 1 {
+                left = (pos, *el);
+            }
+        }
+        let right = digits[left.0 + 1..]
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default();
 
         let num = left.1 * 10 + right;
 
@@ -52,15 +76,41 @@ fn _part_one(input: impl BufRead) -> anyhow::Result<u32> {
 fn _part_two(input: impl BufRead) -> anyhow::Result<usize> {
     input
         .lines()
-        .map(|line| {
-            let mut line: Vec<_> = line?.chars().filter_map(|c| c.to_digit(10)).collect();
-            Ok(max_joltage(&mut line))
+        .enumerate()
+        .map(|(idx, line)| {
+            let mut bank = parse_bank(idx + 1, &line?)?;
+            Ok(max_joltage(&mut bank))
         })
         .sum()
 }
 
+/// Parse the joltages of a bank from its `line` of the input, which needs at least two batteries.
+fn parse_bank(line_no: usize, line: &str) -> Result<Vec<u32>, ParseError> {
+    let bank = line
+        .char_indices()
+        .map(|(pos, c)| {
+            c.to_digit(10).ok_or_else(|| {
+                ParseError::at(
+                    "Battery joltage should be a single digit",
+                    line_no,
+                    line,
+                    &line[pos..pos + c.len_utf8()],
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if bank.len() < 2 {
+        return Err(ParseError::after(
+            "Bank should have at least two batteries",
+            line_no,
+            line,
+        ));
+    }
+    Ok(bank)
+}
+
 fn max_joltage(bank: &mut Vec<u32>) -> usize {
-    while bank.len() > 12 {
+    while bank.len() > BATTERIES_ON {
         let remove_idx = bank
             .windows(2)
             .find_position(|pred| pred[0] < pred[1])
@@ -72,9 +122,88 @@ fn max_joltage(bank: &mut Vec<u32>) -> usize {
     bank.iter().fold(0, |acc, el| acc * 10 + *el as usize)
 }
 
+/// Sum the highest joltage of each bank with `on` batteries turned on, picking each battery in
+/// turn as the highest one that still leaves enough batteries after it for the rest.
+fn _total_joltage_window(input: impl BufRead, on: usize) -> anyhow::Result<usize> {
+    input.lines().enumerate().try_fold(0, |acc, (idx, line)| {
+        let bank = parse_bank(idx + 1, &line?)?;
+        let on = on.min(bank.len());
+
+        let mut joltage = 0;
+        let mut start = 0;
+        for remaining in (0..on).rev() {
+            let window = &bank[start..bank.len() - remaining];
+            // `max_by_key` keeps the last maximum, but the first one leaves the most to choose from
+            let (pos, digit) = window
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, digit)| **digit)
+                .context("Window should never be empty")?;
+            joltage = joltage * 10 + *digit as usize;
+            start += pos + 1;
+        }
+
+        Ok(acc + joltage)
+    })
+}
+
+/// Each line should be a bank of single-digit batteries, with enough of them for part two.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        check_chars(line_no, &line, &DIGITS, &mut violations);
+        let len = line.chars().count();
+        if len < BATTERIES_ON {
+            violations.push(Violation::new(
+                line_no,
+                len + 1,
+                format!("bank has {len} batteries, expected at least {BATTERIES_ON}"),
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+/// How many batteries are in each bank, and how high their joltages go.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let banks: Vec<Vec<u32>> = input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| Ok(parse_bank(idx + 1, &line?)?))
+        .collect::<anyhow::Result<_>>()?;
+
+    let max_joltages = banks
+        .iter()
+        .filter_map(|bank| bank.iter().max())
+        .map(|max| *max as usize);
+
+    Ok(vec![
+        Stat::new("Banks", banks.len()),
+        Stat::new("Batteries per bank", summarize(banks.iter().map(Vec::len))),
+        Stat::new("Highest battery per bank", summarize(max_joltages)),
+    ])
+}
+
+/// `size` banks of 100 batteries each, with joltages from 1 to 9.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..size {
+        let bank: String = (0..100)
+            .map(|_| char::from(b'0' + rng.range(1..=9) as u8))
+            .collect();
+        writeln!(out, "{bank}")?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
+    use crate::{differential, fuzz};
 
     const INPUT: &str = "987654321111111
 811111111111119
@@ -94,4 +223,89 @@ mod tests {
         let actual = _part_two(INPUT.as_bytes()).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(
+                1,
+                5,
+                "unexpected 'a', expected one of '0', '1', '2', '3', '4', '5', '6', '7', '8', '9'",
+            ),
+            Violation::new(2, 4, "bank has 3 batteries, expected at least 12"),
+        ];
+        let actual = validate(Box::new("9876a4321111111\n811".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(3), 50, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = _part_one("987654321111111\n81a1".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 3, "a"), (err.line, err.column(), err.text()));
+
+        let err = _part_two("987654321111111\n8".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 2), (err.line, err.column()));
+    }
+
+    /// Find the highest joltage of each bank by trying every choice of `on` batteries.
+    fn reference_joltage(input: &str, on: usize) -> usize {
+        input
+            .lines()
+            .map(|bank| {
+                let digits: Vec<usize> = bank.bytes().map(|b| (b - b'0') as usize).collect();
+                digits
+                    .iter()
+                    .combinations(on.min(digits.len()))
+                    .map(|chosen| chosen.into_iter().fold(0, |acc, d| acc * 10 + d))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .sum()
+    }
+
+    fn generate_small(rng: &mut Rng) -> String {
+        let mut input = String::new();
+        for _ in 0..rng.range(1..=5) {
+            let len = rng.range(2..=BATTERIES_ON + 4);
+            input.extend((0..len).map(|_| char::from(b'0' + rng.range(0..=9) as u8)));
+            input.push('\n');
+        }
+        input
+    }
+
+    #[test]
+    fn test_part_one_differential() {
+        differential::run(
+            500,
+            generate_small,
+            |input| _part_one(input.as_bytes()).unwrap() as usize,
+            |input| reference_joltage(input, 2),
+        );
+    }
+
+    #[test]
+    fn test_part_two_differential() {
+        differential::run(
+            500,
+            generate_small,
+            |input| _part_two(input.as_bytes()).unwrap(),
+            |input| reference_joltage(input, BATTERIES_ON),
+        );
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(3, 300);
+    }
 }
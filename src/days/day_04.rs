@@ -1,19 +1,42 @@
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 
-use crate::puzzle::Puzzle;
+use crate::{
+    inspect::{Stat, percent},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::Puzzle,
+    rng::Rng,
+    validate::{Violation, check_chars},
+};
 
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![],
+        normalization: Normalization::default(),
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: None,
     }
 }
 
-fn parse_input(input: impl BufRead) -> std::io::Result<Vec<Vec<Space>>> {
-    input
-        .lines()
-        .map(|line| Ok(line?.chars().map(Space::from).collect()))
-        .collect()
+fn parse_input(input: impl BufRead) -> anyhow::Result<Vec<Vec<Space>>> {
+    let mut rows = vec![];
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            return Err(ParseError::after("Expected a row of the grid", idx + 1, &line).into());
+        }
+        let row = line
+            .char_indices()
+            .map(|(pos, c)| Space::parse(idx + 1, &line, pos, c))
+            .collect::<Result<_, _>>()?;
+        rows.push(row);
+    }
+    Ok(rows)
 }
 
 fn part_one(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
@@ -38,12 +61,18 @@ enum Space {
     Empty,
 }
 
-impl From<char> for Space {
-    fn from(value: char) -> Self {
-        match value {
-            '@' => Self::Roll,
-            '.' => Self::Empty,
-            _ => unreachable!("Input should only contain '@' or '.' characters in a line"),
+impl Space {
+    /// Parse the character `c`, found at byte `pos` of its `line` of the input.
+    fn parse(line_no: usize, line: &str, pos: usize, c: char) -> Result<Self, ParseError> {
+        match c {
+            '@' => Ok(Self::Roll),
+            '.' => Ok(Self::Empty),
+            _ => Err(ParseError::at(
+                "Input should only contain '@' or '.' characters in a line",
+                line_no,
+                line,
+                &line[pos..pos + c.len_utf8()],
+            )),
         }
     }
 }
@@ -107,9 +136,65 @@ fn get_adjacent_positions_checked(
     .filter_map(|(a, b)| a.zip(b))
 }
 
+/// The input should be a rectangular grid of `@` rolls and `.` empty spaces.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut width = None;
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        check_chars(line_no, &line, &['@', '.'], &mut violations);
+
+        let len = line.chars().count();
+        let width = *width.get_or_insert(len);
+        if len != width {
+            violations.push(Violation::new(
+                line_no,
+                len.min(width) + 1,
+                format!("row has {len} columns, expected {width}"),
+            ));
+        }
+    }
+    Ok(violations)
+}
+
+/// The size of the grid and how densely packed it is with rolls.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let matrix = parse_input(input)?;
+
+    let rows = matrix.len();
+    let cols = matrix.iter().map(Vec::len).max().unwrap_or_default();
+    let spaces = matrix.iter().map(Vec::len).sum();
+    let rolls = matrix
+        .iter()
+        .flatten()
+        .filter(|space| **space == Space::Roll)
+        .count();
+
+    Ok(vec![
+        Stat::new("Grid size", format!("{rows} x {cols}")),
+        Stat::new("Rolls", rolls),
+        Stat::new("Roll density", percent(rolls, spaces)),
+    ])
+}
+
+/// A `size` x `size` grid, where each space has a 60% chance of holding a roll.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    for _ in 0..size {
+        let row: String = (0..size)
+            .map(|_| if rng.chance(0.6) { '@' } else { '.' })
+            .collect();
+        writeln!(out, "{row}")?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
+    use crate::fuzz;
 
     const INPUT: &str = "..@@.@@@@.
 @@@.@.@.@@
@@ -137,4 +222,39 @@ mod tests {
         let actual = _part_two(&mut input_vec);
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(2, 2, "unexpected '#', expected one of '@', '.'"),
+            Violation::new(3, 3, "row has 2 columns, expected 3"),
+        ];
+        let actual = validate(Box::new("@.@\n.#.\n@@".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(4), 40, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse_input("..@\n.x@".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 2, "x"), (err.line, err.column(), err.text()));
+
+        let err = parse_input("..@\n\n.@@".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 1), (err.line, err.column()));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(4, 300);
+    }
 }
@@ -1,13 +1,41 @@
-use crate::puzzle::Puzzle;
-
-use std::{collections::VecDeque, io::BufRead, str::FromStr};
+use crate::{
+    inspect::{Stat, percent, summarize},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::{Implementation, Puzzle},
+    rng::Rng,
+    validate::{Violation, check_range, check_unsigned},
+};
 
 use anyhow::Context;
 
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    io::{BufRead, Write},
+};
+
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![Implementation {
+            name: "segments",
+            part: 2,
+            solver: Box::new(|input| {
+                let Input { intervals, .. } = parse_input(input)?;
+                Ok(Box::new(_part_two_segments(&intervals)?))
+            }),
+        }],
+        normalization: Normalization {
+            keep_blank_lines: true,
+            ..Normalization::default()
+        },
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: None,
     }
 }
 
@@ -21,76 +49,165 @@ struct Interval {
     end: usize,
 }
 
-impl FromStr for Interval {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s
-            .split_once('-')
-            .context("Interval format should always be {start}-{end}")?;
-        let start: usize = start
-            .parse()
-            .context("Interval start should be valid usize")?;
-        let end: usize = end.parse().context("Interval end should be valid usize")?;
+impl Interval {
+    /// Parse an interval from its `line` of the input, which should be in the form `{start}-{end}`.
+    fn parse(line_no: usize, line: &str) -> Result<Self, ParseError> {
+        let (start, end) = line.split_once('-').ok_or_else(|| {
+            ParseError::at(
+                "Interval format should always be {start}-{end}",
+                line_no,
+                line,
+                line,
+            )
+        })?;
+        let parse_bound = |bound: &str, what: &str| {
+            bound.parse::<usize>().map_err(|_| {
+                ParseError::at(
+                    format!("Interval {what} should be valid usize"),
+                    line_no,
+                    line,
+                    bound,
+                )
+            })
+        };
+        let start = parse_bound(start, "start")?;
+        let end = parse_bound(end, "end")?;
+        if start > end {
+            return Err(ParseError::at(
+                "Interval start should not be greater than its end",
+                line_no,
+                line,
+                line,
+            ));
+        }
 
         Ok(Self { start, end })
     }
 }
 
-fn parse_input(raw: impl BufRead) -> Input<impl Iterator<Item = usize>> {
-    let mut lines = raw.lines();
+/// Everything that can go wrong while reading the input, each pointing at where it happened.
+#[derive(Debug)]
+enum InputError {
+    Io(std::io::Error),
+    /// The input ended without a blank line separating intervals from IDs
+    MissingSeparator(ParseError),
+    /// The blank separator line wasn't preceded by any intervals
+    NoIntervals(ParseError),
+    MalformedInterval(ParseError),
+    InvalidId(ParseError),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Io(_) => "Could not read input",
+            Self::MissingSeparator(_) => "Missing blank separator line",
+            Self::NoIntervals(_) => "No intervals",
+            Self::MalformedInterval(_) => "Malformed interval",
+            Self::InvalidId(_) => "Invalid ID",
+        })
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::MissingSeparator(e)
+            | Self::NoIntervals(e)
+            | Self::MalformedInterval(e)
+            | Self::InvalidId(e) => Some(e),
+        }
+    }
+}
+
+fn parse_input(
+    raw: impl BufRead,
+) -> Result<Input<impl Iterator<Item = Result<usize, InputError>>>, InputError> {
+    let mut lines = raw
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.map_err(InputError::Io)));
 
     let mut intervals = VecDeque::new();
-    while let interval_raw = lines
-        .next()
-        .expect("Input should have content for interval")
-        .expect("Should be able to read valid line for interval")
+    let mut last_line = (0, String::new());
+    loop {
+        let Some((line_no, interval_raw)) = lines.next() else {
+            let (line_no, line) = last_line;
+            return Err(InputError::MissingSeparator(ParseError::after(
+                "Expected a blank line between the intervals and the IDs",
+                line_no,
+                &line,
+            )));
+        };
+        let interval_raw = interval_raw?;
+
         // Empty line denotes input's separation between intervals and IDs
-        && !interval_raw.is_empty()
-    {
-        let interval = interval_raw.parse().unwrap();
+        if interval_raw.is_empty() {
+            if intervals.is_empty() {
+                return Err(InputError::NoIntervals(ParseError::after(
+                    "Expected intervals before the blank line",
+                    line_no,
+                    &interval_raw,
+                )));
+            }
+            break;
+        }
+
+        let interval =
+            Interval::parse(line_no, &interval_raw).map_err(InputError::MalformedInterval)?;
         intervals.push_back(interval);
+        last_line = (line_no, interval_raw);
     }
 
     // The rest of the contents of the input is just the IDs
-    let ids = lines.map(|line| {
-        line.expect("Should read valid line for ID")
-            .parse()
-            .expect("Input ID line should contain only a single valid usize")
+    let ids = lines.map(|(line_no, line)| {
+        let line = line?;
+        line.parse().map_err(|_| {
+            InputError::InvalidId(ParseError::at(
+                "Input ID line should contain only a single valid usize",
+                line_no,
+                &line,
+                &line,
+            ))
+        })
     });
 
-    Input { intervals, ids }
+    Ok(Input { intervals, ids })
 }
 
 fn part_one(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    let input = parse_input(input);
-    let ans = _part_one(input);
+    let input = parse_input(input)?;
+    let ans = _part_one(input)?;
     Ok(Box::new(ans))
 }
 
-fn _part_one<I: Iterator<Item = usize>>(input: Input<I>) -> usize {
-    input
-        .ids
-        .filter(|id| {
-            for interval in &input.intervals {
-                if (interval.start..=interval.end).contains(id) {
-                    return true;
-                }
-            }
-            false
-        })
-        .count()
+fn _part_one<I>(input: Input<I>) -> Result<usize, InputError>
+where
+    I: Iterator<Item = Result<usize, InputError>>,
+{
+    let Input { intervals, mut ids } = input;
+    ids.try_fold(0, |acc, id| {
+        let id = id?;
+        let is_fresh = intervals
+            .iter()
+            .any(|interval| (interval.start..=interval.end).contains(&id));
+        Ok(acc + usize::from(is_fresh))
+    })
 }
 
 fn part_two(input: impl BufRead) -> anyhow::Result<crate::puzzle::Answer> {
-    let Input { intervals, .. } = parse_input(input);
-    Ok(Box::new(_part_two(intervals)))
+    let Input { intervals, .. } = parse_input(input)?;
+    Ok(Box::new(_part_two(intervals)?))
 }
 
-fn _part_two(mut input: VecDeque<Interval>) -> usize {
+fn _part_two(mut input: VecDeque<Interval>) -> anyhow::Result<usize> {
     // Merge overlapping intervals
     input.make_contiguous().sort_by_key(|r| r.start);
-    let mut ret = vec![input.pop_front().unwrap()];
+    let Some(first) = input.pop_front() else {
+        return Ok(0);
+    };
+    let mut ret = vec![first];
     for cur in input {
         let prev = ret.last_mut().unwrap();
         if cur.start <= prev.end {
@@ -101,13 +218,122 @@ fn _part_two(mut input: VecDeque<Interval>) -> usize {
     }
 
     ret.into_iter()
-        .map(|range| range.end - range.start + 1)
-        .sum()
+        .try_fold(0usize, |acc, range| {
+            (range.end - range.start)
+                .checked_add(1)
+                .and_then(|width| acc.checked_add(width))
+        })
+        .context("Number of fresh IDs is too large for a usize")
+}
+
+/// Count the IDs covered by any interval without merging them, by splitting the IDs into
+/// segments at every interval's bounds and checking each segment against every interval.
+fn _part_two_segments(intervals: &VecDeque<Interval>) -> anyhow::Result<usize> {
+    // Widened so that the bound just past an interval ending at `usize::MAX` still fits
+    let mut bounds: Vec<u128> = intervals
+        .iter()
+        .flat_map(|interval| [interval.start as u128, interval.end as u128 + 1])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let covered: u128 = bounds
+        .windows(2)
+        .filter(|segment| {
+            intervals.iter().any(|interval| {
+                (interval.start as u128..=interval.end as u128).contains(&segment[0])
+            })
+        })
+        .map(|segment| segment[1] - segment[0])
+        .sum();
+    usize::try_from(covered).context("Number of fresh IDs is too large for a usize")
+}
+
+/// The input should be `{start}-{end}` intervals, one per line, then a blank line, then IDs.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut interval_ct = 0;
+    let mut separator = None;
+    let mut last_line_no = 0;
+
+    for (idx, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = idx + 1;
+        last_line_no = line_no;
+
+        if separator.is_some() {
+            check_unsigned::<usize>(line_no, 1, &line, "ID", &mut violations);
+        } else if line.is_empty() {
+            separator = Some(line_no);
+        } else {
+            interval_ct += 1;
+            check_range::<usize>(line_no, 1, &line, &mut violations);
+        }
+    }
+
+    match separator {
+        None => violations.push(Violation::new(
+            last_line_no + 1,
+            1,
+            "missing blank line between the intervals and the IDs",
+        )),
+        Some(line_no) if interval_ct == 0 => violations.push(Violation::new(
+            line_no,
+            1,
+            "no intervals before the blank line",
+        )),
+        Some(_) => {}
+    }
+
+    Ok(violations)
+}
+
+/// How many intervals and IDs there are, and how much the intervals overlap one another.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let Input { intervals, ids } = parse_input(input)?;
+    let id_ct = ids.collect::<Result<Vec<_>, _>>()?.len();
+
+    let interval_ct = intervals.len();
+    let widths: Vec<usize> = intervals
+        .iter()
+        .map(|interval| (interval.end - interval.start).saturating_add(1))
+        .collect();
+    let total_width = widths
+        .iter()
+        .fold(0, |acc: usize, w| acc.saturating_add(*w));
+    let covered = _part_two(intervals)?;
+
+    Ok(vec![
+        Stat::new("Intervals", interval_ct),
+        Stat::new("IDs", id_ct),
+        Stat::new("Interval width", summarize(widths)),
+        Stat::new("IDs covered by intervals", covered),
+        Stat::new("Overlap ratio", percent(total_width - covered, total_width)),
+    ])
+}
+
+/// `size` intervals of up to ten billion IDs each, followed by `size` IDs to check.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    const MAX_ID: usize = 1_000_000_000_000;
+
+    for _ in 0..size {
+        let start = rng.range(1..=MAX_ID);
+        let end = start + rng.range(0..=10_000_000_000);
+        writeln!(out, "{start}-{end}")?;
+    }
+    writeln!(out)?;
+    for _ in 0..size {
+        writeln!(out, "{}", rng.range(1..=MAX_ID))?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::BTreeSet, io::Cursor};
+
     use super::*;
+    use crate::{differential, fuzz};
 
     const INPUT: &str = "3-5
 10-14
@@ -124,16 +350,99 @@ mod tests {
     #[test]
     fn test_part_one() {
         let expected = 3;
-        let input = parse_input(INPUT.as_bytes());
-        let actual = _part_one(input);
+        let input = parse_input(INPUT.as_bytes()).unwrap();
+        let actual = _part_one(input).unwrap();
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn test_part_two() {
         let expected = 14;
-        let input = parse_input(INPUT.as_bytes());
-        let actual = _part_two(input.intervals);
+        let input = parse_input(INPUT.as_bytes()).unwrap();
+        let actual = _part_two(input.intervals).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("3-5\n10-14".as_bytes()).err().unwrap();
+        assert!(matches!(err, InputError::MissingSeparator(ref e) if e.line == 2));
+
+        let err = parse_input("\n1".as_bytes()).err().unwrap();
+        assert!(matches!(err, InputError::NoIntervals(ref e) if e.line == 1));
+
+        let err = parse_input("3-5\n10-x\n\n1".as_bytes()).err().unwrap();
+        assert!(
+            matches!(err, InputError::MalformedInterval(ref e) if (e.line, e.column(), e.text()) == (2, 4, "x"))
+        );
+
+        let input = parse_input("3-5\n\n1\nx".as_bytes()).unwrap();
+        let err = _part_one(input).unwrap_err();
+        assert!(matches!(err, InputError::InvalidId(ref e) if (e.line, e.text()) == (4, "x")));
+
+        assert_eq!(0, _part_two(VecDeque::new()).unwrap());
+
+        let input = parse_input("0-18446744073709551615\n\n1".as_bytes()).unwrap();
+        assert!(_part_two(input.intervals).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(2, 1, "malformed range \"10\", expected {start}-{end}"),
+            Violation::new(4, 1, "missing blank line between the intervals and the IDs"),
+        ];
+        let actual = validate(Box::new("3-5\n10\n16-20".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+
+        let expected = vec![
+            Violation::new(3, 3, "unexpected '-' in ID, expected a digit"),
+            Violation::new(1, 1, "no intervals before the blank line"),
+        ];
+        let actual = validate(Box::new("\n1\n16-20".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(5), 100, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    /// Count the IDs covered by any interval by collecting every one of them.
+    fn reference_part_two(input: &str) -> usize {
+        let mut covered = BTreeSet::new();
+        for line in input.lines().take_while(|line| !line.is_empty()) {
+            let (start, end) = line.split_once('-').unwrap();
+            covered.extend(start.parse::<usize>().unwrap()..=end.parse().unwrap());
+        }
+        covered.len()
+    }
+
+    #[test]
+    fn test_part_two_differential() {
+        differential::run(
+            500,
+            |rng| {
+                let mut input = String::new();
+                for _ in 0..rng.range(1..=8) {
+                    let start = rng.range(0..=60);
+                    let end = start + rng.range(0..=15);
+                    input.push_str(&format!("{start}-{end}\n"));
+                }
+                input.push_str("\n1\n");
+                input
+            },
+            |input| _part_two(parse_input(input.as_bytes()).unwrap().intervals).unwrap(),
+            reference_part_two,
+        );
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(5, 300);
+    }
 }
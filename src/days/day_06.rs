@@ -1,16 +1,31 @@
-use anyhow::Context;
+use crate::{
+    inspect::{Stat, summarize},
+    normalize::{Normalization, TrailingWhitespace},
+    parse::ParseError,
+    puzzle::Puzzle,
+    rng::Rng,
+    validate::{DIGITS, Violation, check_chars},
+};
 
-use crate::puzzle::Puzzle;
+use std::io::{BufRead, Write};
 
-use std::{
-    io::BufRead,
-    ops::{Add, Mul},
-};
+use anyhow::Context;
 
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![],
+        normalization: Normalization {
+            // Part two reads the numbers by column, so alignment has to be kept intact
+            trailing_whitespace: TrailingWhitespace::Preserve,
+            ..Default::default()
+        },
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: None,
     }
 }
 
@@ -18,26 +33,21 @@ fn part_one(mut input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer
     let mut buf = String::new();
     input.read_to_string(&mut buf)?;
     let parsed_input = ParsedInput::from_raw_input(buf)?;
-    let ans = _part_one(parsed_input);
+    let ans = _part_one(parsed_input)?;
     Ok(Box::new(ans))
 }
 
-fn _part_one(input: ParsedInput) -> usize {
-    input
-        .columns
-        .into_iter()
-        .map(|col| {
-            col.nums
-                .into_iter()
-                .reduce(|acc, el| match col.op {
-                    Operator::Add => acc + el,
-                    Operator::Mul => acc * el,
-                })
-                .expect("Input should not be empty")
-        })
-        .sum()
+fn _part_one(input: ParsedInput) -> anyhow::Result<usize> {
+    input.columns.into_iter().try_fold(0usize, |ans, col| {
+        col.op
+            .apply_all(&col.nums)
+            .and_then(|result| ans.checked_add(result))
+            .context(OVERFLOW_MESSAGE)
+    })
 }
 
+const OVERFLOW_MESSAGE: &str = "Answer is too large for a usize";
+
 fn part_two(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
     let ans = _part_two(input)?;
     Ok(Box::new(ans))
@@ -45,20 +55,42 @@ fn part_two(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
 
 fn _part_two(input: impl BufRead) -> anyhow::Result<usize> {
     let mut ans: usize = 0;
-    let mut input_lines: Vec<Vec<char>> = input
-        .lines()
-        .map(|line_string| line_string.map(|line| line.chars().collect()))
-        .collect::<Result<_, _>>()?;
-    let mut ops = input_lines
+    let mut input_lines: Vec<String> = input.lines().collect::<Result<_, _>>()?;
+    let ops_line_no = input_lines.len();
+    let ops_line = input_lines
         .pop()
-        .expect("Input shouldn't be empty")
+        .ok_or_else(|| ParseError::after("Input shouldn't be empty", 1, ""))?;
+    let mut ops = ops_line
+        .char_indices()
+        .filter(|(_, pat)| !pat.is_whitespace())
+        .map(|(idx, pat)| {
+            let token = &ops_line[idx..idx + pat.len_utf8()];
+            Operator::parse(ops_line_no, &ops_line, token)
+        });
+    let first_line = input_lines.first().cloned().unwrap_or_default();
+    let width = first_line.chars().count();
+    for (idx, line) in input_lines.iter().enumerate() {
+        let len = line.chars().count();
+        if len != width {
+            return Err(ParseError::new(
+                format!("Row should be as wide as the first row, {width}"),
+                idx + 1,
+                line,
+                len.min(width)..len.max(width),
+            )
+            .into());
+        }
+    }
+    let input_lines: Vec<Vec<char>> = input_lines
         .into_iter()
-        .filter(|pat| !pat.is_whitespace())
-        .map(Operator::from);
+        .map(|line| line.chars().collect())
+        .collect();
 
-    let mut cur_op = ops.next().context("Operators line is empty")?;
+    let mut cur_op = ops
+        .next()
+        .ok_or_else(|| ParseError::after("Operators line is empty", ops_line_no, &ops_line))??;
     let mut working_column = vec![];
-    for c in 0..input_lines[0].len() {
+    for c in 0..width {
         let mut col_digits = vec![];
         for row in &input_lines {
             let col_digit = match row[c] {
@@ -70,27 +102,52 @@ fn _part_two(input: impl BufRead) -> anyhow::Result<usize> {
         let digits_to_sum = col_digits.into_iter().flatten().collect::<Vec<_>>();
         if digits_to_sum.is_empty() {
             // whitespace gap between columns - perform op & advance
-            ans += std::mem::take(&mut working_column)
-                .into_iter()
-                .reduce(|acc, el| cur_op.apply(acc, el))
-                .context("Problem buffer is empty")?;
+            if working_column.is_empty() {
+                return Err(empty_problem_error(&first_line, c).into());
+            }
+            ans = cur_op
+                .apply_all(&std::mem::take(&mut working_column))
+                .and_then(|result| ans.checked_add(result))
+                .context(OVERFLOW_MESSAGE)?;
 
-            cur_op = ops
-                .next()
-                .context("Not enough operators for provided numbers")?;
+            cur_op = ops.next().ok_or_else(|| {
+                ParseError::after(
+                    "Not enough operators for provided numbers",
+                    ops_line_no,
+                    &ops_line,
+                )
+            })??;
         } else {
-            let val = digits_to_sum.into_iter().fold(0, |acc, el| acc * 10 + el);
+            let val = digits_to_sum
+                .into_iter()
+                .try_fold(0usize, |acc, el| acc.checked_mul(10)?.checked_add(el))
+                .context("Number is too large for a usize")?;
             working_column.push(val);
         }
     }
 
     // Once more for the final problem
-    ans += working_column
-        .into_iter()
-        .reduce(|acc, el| cur_op.apply(acc, el))
-        .expect("Should always be Some, given the input format");
+    if working_column.is_empty() {
+        return Err(empty_problem_error(&first_line, width).into());
+    }
+    cur_op
+        .apply_all(&working_column)
+        .and_then(|result| ans.checked_add(result))
+        .context(OVERFLOW_MESSAGE)
+}
 
-    Ok(ans)
+/// Error for a problem with no numbers, which ends at column `c` of the worksheet.
+fn empty_problem_error(first_line: &str, c: usize) -> ParseError {
+    let start = first_line
+        .char_indices()
+        .nth(c)
+        .map_or(first_line.len(), |(idx, _)| idx);
+    ParseError::new(
+        "Problem buffer is empty, expected a column of digits",
+        1,
+        first_line,
+        start..start + 1,
+    )
 }
 
 struct ParsedInput {
@@ -108,53 +165,50 @@ enum Operator {
 }
 
 impl Operator {
-    fn apply<L, R, O>(&self, lhs: L, rhs: R) -> O
-    where
-        L: Add<R, Output = O>,
-        L: Mul<R, Output = O>,
-    {
-        match self {
-            Self::Add => lhs + rhs,
-            Self::Mul => lhs * rhs,
-        }
-    }
-}
-
-impl From<char> for Operator {
-    fn from(value: char) -> Self {
-        match value {
-            '+' => Self::Add,
-            '*' => Self::Mul,
-            _ => unreachable!("For this puzzle, only add and mul should be present"),
-        }
+    /// Apply the operator across all of the `nums`, or `None` if there are none or the result
+    /// overflows.
+    fn apply_all(&self, nums: &[usize]) -> Option<usize> {
+        let (first, rest) = nums.split_first()?;
+        rest.iter().try_fold(*first, |acc, el| match self {
+            Self::Add => acc.checked_add(*el),
+            Self::Mul => acc.checked_mul(*el),
+        })
     }
-}
 
-impl From<&str> for Operator {
-    fn from(value: &str) -> Self {
-        match value {
-            "+" => Self::Add,
-            "*" => Self::Mul,
-            _ => unreachable!("For this puzzle, only add and mul should be present"),
+    /// Parse an operator from its `token`, which is a subslice of `line`.
+    fn parse(line_no: usize, line: &str, token: &str) -> Result<Self, ParseError> {
+        match token {
+            "+" => Ok(Self::Add),
+            "*" => Ok(Self::Mul),
+            _ => Err(ParseError::at(
+                "For this puzzle, only add and mul should be present",
+                line_no,
+                line,
+                token,
+            )),
         }
     }
 }
 
 impl ParsedInput {
-    fn from_raw_input(input: impl AsRef<str>) -> anyhow::Result<Self> {
+    fn from_raw_input(input: impl AsRef<str>) -> Result<Self, ParseError> {
         let input = input.as_ref();
-        let mut lines_iter = input.lines().map(|line| line.split_whitespace()).rev();
-        let ops_iter = lines_iter.next().unwrap().map(Operator::from);
+        let lines: Vec<&str> = input.lines().collect();
+        let Some((ops_line, num_lines)) = lines.split_last() else {
+            return Err(ParseError::after("Input shouldn't be empty", 1, ""));
+        };
+        let ops_line_no = lines.len();
+        let ops = ops_line
+            .split_whitespace()
+            .map(|token| Ok((token, Operator::parse(ops_line_no, ops_line, token)?)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
 
         let mut nums_list: Vec<Vec<usize>> = vec![];
-        let nums_iter = lines_iter.rev().map(|num_str| {
-            num_str
-                .into_iter()
-                .map(|el| el.parse::<usize>().unwrap())
-                .enumerate()
-        });
-        for num_line in nums_iter {
-            for (col_num, num) in num_line {
+        for (idx, line) in num_lines.iter().enumerate() {
+            for (col_num, num_str) in line.split_whitespace().enumerate() {
+                let num = num_str.parse::<usize>().map_err(|_| {
+                    ParseError::at("Could not parse valid number", idx + 1, line, num_str)
+                })?;
                 match nums_list.get_mut(col_num) {
                     Some(list) => list.push(num),
                     None => nums_list.insert(col_num, vec![num]),
@@ -162,21 +216,150 @@ impl ParsedInput {
             }
         }
 
-        let columns = ops_iter
+        let columns = ops
+            .into_iter()
             .enumerate()
-            .map(|(idx, op)| Column {
-                nums: std::mem::take(&mut nums_list[idx]),
-                op,
+            .map(|(idx, (token, op))| {
+                let nums = nums_list.get_mut(idx).map(std::mem::take).ok_or_else(|| {
+                    ParseError::at("No numbers for this operator", ops_line_no, ops_line, token)
+                })?;
+                Ok(Column { nums, op })
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
 
         Ok(Self { columns })
     }
 }
 
+/// The input should be rows of numbers aligned into columns, then a row of `+`/`*` operators
+/// with one per problem. Every row needs the same width, since part two reads it by column.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let lines: Vec<String> = input.lines().collect::<Result<_, _>>()?;
+    let Some((ops, num_rows)) = lines.split_last() else {
+        violations.push(Violation::new(1, 1, "empty input, expected a worksheet"));
+        return Ok(violations);
+    };
+
+    if num_rows.is_empty() {
+        violations.push(Violation::new(
+            1,
+            1,
+            "missing rows of numbers above the operators",
+        ));
+    }
+
+    let problem_ct = ops.chars().filter(|c| matches!(c, '+' | '*')).count();
+    let num_chars = [DIGITS.as_slice(), &[' ']].concat();
+    let width = lines[0].chars().count();
+    for (idx, line) in lines.iter().enumerate() {
+        let line_no = idx + 1;
+        if line_no == lines.len() {
+            check_chars(line_no, line, &['+', '*', ' '], &mut violations);
+        } else {
+            check_chars(line_no, line, &num_chars, &mut violations);
+            let num_ct = line.split_whitespace().count();
+            if num_ct != problem_ct {
+                violations.push(Violation::new(
+                    line_no,
+                    1,
+                    format!("row has {num_ct} numbers, expected {problem_ct} (one per operator)"),
+                ));
+            }
+        }
+
+        let len = line.chars().count();
+        if len != width {
+            violations.push(Violation::new(
+                line_no,
+                len.min(width) + 1,
+                format!("row has {len} columns, expected {width}"),
+            ));
+        }
+    }
+
+    Ok(violations)
+}
+
+/// How many problems there are of each kind, and how large they get.
+fn inspect(mut input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let mut buf = String::new();
+    input.read_to_string(&mut buf)?;
+    let parsed_input = ParsedInput::from_raw_input(buf)?;
+
+    let columns = &parsed_input.columns;
+    let add_ct = columns
+        .iter()
+        .filter(|col| matches!(col.op, Operator::Add))
+        .count();
+    let digit_cts = columns
+        .iter()
+        .flat_map(|col| &col.nums)
+        .map(|num| num.to_string().len());
+
+    Ok(vec![
+        Stat::new("Problems", columns.len()),
+        Stat::new(
+            "Add / mul",
+            format!("{add_ct} / {}", columns.len() - add_ct),
+        ),
+        Stat::new(
+            "Numbers per problem",
+            summarize(columns.iter().map(|col| col.nums.len())),
+        ),
+        Stat::new("Digits per number", summarize(digit_cts)),
+    ])
+}
+
+/// `size` problems side by side, each a column of four numbers with its operator underneath.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    const NUM_ROWS: usize = 4;
+
+    let mut rows = vec![String::new(); NUM_ROWS];
+    let mut ops = String::new();
+    for problem in 0..size {
+        if problem > 0 {
+            rows.iter_mut().for_each(|row| row.push(' '));
+            ops.push(' ');
+        }
+
+        // Capped at three digits, so that the answer doesn't overflow for large sizes
+        let width = rng.range(1..=3);
+        // One number always spans the full width, so that no column of the problem is blank
+        let widest = rng.range(0..=NUM_ROWS - 1);
+        let left_aligned = rng.chance(0.5);
+        for (idx, row) in rows.iter_mut().enumerate() {
+            let digits = if idx == widest {
+                width
+            } else {
+                rng.range(1..=width)
+            };
+            let num: String = (0..digits)
+                .map(|_| char::from(b'0' + rng.range(1..=9) as u8))
+                .collect();
+            row.push_str(&if left_aligned {
+                format!("{num:<width$}")
+            } else {
+                format!("{num:>width$}")
+            });
+        }
+
+        let op = if rng.chance(0.5) { '+' } else { '*' };
+        ops.push_str(&format!("{op:<width$}"));
+    }
+
+    for row in rows {
+        writeln!(out, "{row}")?;
+    }
+    writeln!(out, "{ops}")
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
+    use crate::fuzz;
 
     const INPUT: &str = "123 328  51 64 
  45 64  387 23 
@@ -187,7 +370,7 @@ mod tests {
     fn test_part_one() {
         let expected = 4277556;
         let parsed_input = ParsedInput::from_raw_input(INPUT).unwrap();
-        let actual = _part_one(parsed_input);
+        let actual = _part_one(parsed_input).unwrap();
         assert_eq!(expected, actual);
     }
 
@@ -197,4 +380,49 @@ mod tests {
         let actual = _part_two(INPUT.as_bytes()).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(2, 1, "row has 1 numbers, expected 2 (one per operator)"),
+            Violation::new(2, 3, "row has 2 columns, expected 5"),
+            Violation::new(3, 3, "unexpected '-', expected one of '+', '*', ' '"),
+        ];
+        let actual = validate(Box::new("12 34\n 5\n* - +".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(6), 100, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = ParsedInput::from_raw_input("12 34\n5 6\n* -")
+            .err()
+            .unwrap();
+        assert_eq!((3, 3, "-"), (err.line, err.column(), err.text()));
+
+        let err = ParsedInput::from_raw_input("12 3x\n5 6\n* +")
+            .err()
+            .unwrap();
+        assert_eq!((1, 4, "3x"), (err.line, err.column(), err.text()));
+
+        let err = _part_two("12 34\n5 6\n*  +".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 4), (err.line, err.column()));
+
+        let input = ParsedInput::from_raw_input("18446744073709551615 1\n2 3\n* +").unwrap();
+        assert!(_part_one(input).is_err());
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(6, 300);
+    }
 }
@@ -1,13 +1,30 @@
-use anyhow::Context;
+use itertools::Itertools;
 
-use crate::puzzle::Puzzle;
+use crate::{
+    inspect::{Stat, summarize},
+    normalize::Normalization,
+    parse::ParseError,
+    puzzle::Puzzle,
+    rng::Rng,
+    validate::{Violation, check_chars},
+};
 
-use std::{collections::HashSet, io::BufRead};
+use std::{
+    collections::HashSet,
+    io::{BufRead, Write},
+};
 
 pub(crate) fn get_puzzle() -> crate::puzzle::Puzzle {
     Puzzle {
         part_one: Box::new(part_one),
         part_two: Box::new(part_two),
+        alternatives: vec![],
+        normalization: Normalization::default(),
+        validate: Box::new(validate),
+        inspect: Box::new(inspect),
+        generate: Box::new(generate),
+        lint: None,
+        trace: None,
     }
 }
 
@@ -16,8 +33,8 @@ fn part_one(input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
     Ok(Box::new(ans))
 }
 
-fn part_two(mut _input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
-    todo!()
+fn part_two(_input: Box<dyn BufRead>) -> anyhow::Result<crate::puzzle::Answer> {
+    anyhow::bail!("Day 7, Part 2 is not yet solved")
 }
 
 const START: char = 'S';
@@ -28,17 +45,29 @@ fn _part_one(input: impl BufRead) -> anyhow::Result<usize> {
     let mut beam_cols = HashSet::new();
     let mut lines = input.lines();
 
-    beam_cols.insert(
-        lines
-            .next()
-            .context("Input is empty")??
-            .chars()
-            .position(|c| c == START)
-            .context("'S' start point is missing'")?,
-    );
+    let first = lines
+        .next()
+        .transpose()?
+        .ok_or_else(|| ParseError::after("Input shouldn't be empty", 1, ""))?;
+    let start = first
+        .find(START)
+        .ok_or_else(|| ParseError::after("'S' start point is missing", 1, &first))?;
+    beam_cols.insert(start);
 
-    for line in lines {
+    for (idx, line) in lines.enumerate() {
         let line = line?;
+        let line_no = idx + 2;
+        if line.len() != first.len() {
+            let span = line.len().min(first.len())..line.len().max(first.len());
+            return Err(ParseError::new(
+                format!("Row should be as wide as the first row, {}", first.len()),
+                line_no,
+                &line,
+                span,
+            )
+            .into());
+        }
+
         let line_bytes = line.as_bytes();
         let to_check: Vec<_> = beam_cols
             .iter()
@@ -47,10 +76,16 @@ fn _part_one(input: impl BufRead) -> anyhow::Result<usize> {
 
         for (idx, c) in to_check {
             if c == SPLITTER {
+                let (Some(left), right) = (idx.checked_sub(1), idx + 1) else {
+                    return Err(edge_splitter_error(line_no, &line, idx).into());
+                };
+                if right >= line.len() {
+                    return Err(edge_splitter_error(line_no, &line, idx).into());
+                }
                 split_count += 1;
                 beam_cols.remove(&idx);
-                beam_cols.insert(idx - 1);
-                beam_cols.insert(idx + 1);
+                beam_cols.insert(left);
+                beam_cols.insert(right);
             }
         }
     }
@@ -58,9 +93,118 @@ fn _part_one(input: impl BufRead) -> anyhow::Result<usize> {
     Ok(split_count)
 }
 
+/// Error for a beam hitting the splitter at byte `idx` of the `line`, which sits on the edge.
+fn edge_splitter_error(line_no: usize, line: &str, idx: usize) -> ParseError {
+    ParseError::new(
+        "Splitter on the edge of the manifold would split a beam outside of it",
+        line_no,
+        line,
+        idx..idx + 1,
+    )
+}
+
+/// The first line should hold the single `S` start point, then the rest are rows of `^`
+/// splitters, all the same width. A splitter can't sit on either edge of the manifold, since
+/// it would split a beam outside of it.
+fn validate(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>> {
+    let mut violations = vec![];
+    let mut lines = input.lines();
+
+    let Some(first) = lines.next().transpose()? else {
+        violations.push(Violation::new(1, 1, "empty input, expected a manifold"));
+        return Ok(violations);
+    };
+    check_chars(1, &first, &['.', START], &mut violations);
+    let start_ct = first.matches(START).count();
+    if start_ct != 1 {
+        violations.push(Violation::new(
+            1,
+            1,
+            format!("found {start_ct} '{START}' start points, expected exactly one"),
+        ));
+    }
+
+    let width = first.chars().count();
+    for (idx, line) in lines.enumerate() {
+        let line = line?;
+        let line_no = idx + 2;
+        check_chars(line_no, &line, &['.', SPLITTER], &mut violations);
+
+        let len = line.chars().count();
+        if len != width {
+            violations.push(Violation::new(
+                line_no,
+                len.min(width) + 1,
+                format!("row has {len} columns, expected {width}"),
+            ));
+        }
+        let edges = [(1, line.chars().next()), (len, line.chars().last())];
+        for (column, c) in edges.into_iter().dedup_by(|a, b| a.0 == b.0) {
+            if c == Some(SPLITTER) {
+                violations.push(Violation::new(
+                    line_no,
+                    column,
+                    "splitter on the edge of the manifold",
+                ));
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+/// The size of the manifold, and how many splitters sit on each of its rows.
+fn inspect(input: Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>> {
+    let lines: Vec<String> = input.lines().collect::<Result<_, _>>()?;
+
+    let width = lines.first().map(|line| line.len()).unwrap_or_default();
+    let splitter_cts: Vec<usize> = lines
+        .iter()
+        .map(|line| line.matches(SPLITTER).count())
+        .filter(|ct| *ct > 0)
+        .collect();
+
+    Ok(vec![
+        Stat::new("Manifold size", format!("{} x {width}", lines.len())),
+        Stat::new("Splitters", splitter_cts.iter().sum::<usize>()),
+        Stat::new("Rows with splitters", splitter_cts.len()),
+        Stat::new("Splitters by row", splitter_cts.iter().join(", ")),
+        Stat::new("Splitters per row", summarize(splitter_cts)),
+    ])
+}
+
+/// A manifold `size` rows tall and `size` columns wide (rounded up to be odd), with the start
+/// in the middle of the top row and splitters scattered along every other row below it.
+fn generate(rng: &mut Rng, size: usize, out: &mut dyn Write) -> std::io::Result<()> {
+    let width = size.max(3) | 1;
+    let first: String = (0..width)
+        .map(|col| if col == width / 2 { START } else { '.' })
+        .collect();
+    writeln!(out, "{first}")?;
+
+    for row in 1..size.max(2) {
+        let line: String = (0..width)
+            .map(|col| {
+                // Splitters are kept off of the edges, so that beams stay within the manifold
+                let inner = col > 0 && col < width - 1;
+                if row % 2 == 0 && inner && rng.chance(0.3) {
+                    SPLITTER
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(out, "{line}")?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
+    use crate::fuzz;
 
     const INPUT: &str = ".......S.......
 ...............
@@ -85,4 +229,42 @@ mod tests {
         let actual = _part_one(INPUT.as_bytes()).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(Box::new(INPUT.as_bytes())).unwrap().is_empty());
+
+        let expected = vec![
+            Violation::new(1, 1, "found 2 'S' start points, expected exactly one"),
+            Violation::new(2, 3, "unexpected 'x', expected one of '.', '^'"),
+            Violation::new(3, 5, "splitter on the edge of the manifold"),
+        ];
+        let actual = validate(Box::new("S...S\n..x..\n...^^".as_bytes())).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut input = vec![];
+        generate(&mut Rng::new(7), 41, &mut input).unwrap();
+        assert!(validate(Box::new(Cursor::new(input))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::run(7, 300);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = _part_one("..S..\n...".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((2, 4), (err.line, err.column()));
+
+        let err = _part_one("S..\n...\n^..".as_bytes()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!((3, 1, "^"), (err.line, err.column(), err.text()));
+
+        assert!(_part_one("...\n...".as_bytes()).is_err());
+    }
 }
@@ -6,32 +6,137 @@ mod day_05;
 mod day_06;
 mod day_07;
 
-use crate::puzzle::Puzzle;
+pub use day_01::{Wheel, count_online, simulate_lock};
+pub use day_02::{
+    Counting, IdOptions, IdRanges, Invalid, OVERFLOW_MESSAGE, find_invalid_ids,
+    itemize_invalid_ids, to_radix,
+};
+
+use crate::{
+    normalize::NormalizedInput,
+    parse::{self, ParseMode},
+    puzzle::{Answer, DEFAULT_IMPL, Puzzle},
+};
+
+use anyhow::Context;
 
 use std::{
     fs::File,
     io::{BufRead, BufReader},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 const INPUTS_DIR_NAME: &str = "inputs";
 const INPUT_FILE_NAME_PREFIX: &str = "day_";
 const INPUT_FILE_EXTENSION: &str = "txt";
+/// Extensions of compressed inputs, tried in order if the plain input file doesn't exist
+const COMPRESSED_EXTENSIONS: [&str; 2] = ["gz", "zst"];
 
-/// Get the input data for the provided day, in the form of a [`BufRead`].
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Get the directory holding the inputs for the given `profile`.
 ///
-/// This data must already exist on disk in the `inputs/` directory, named
-/// as `day_N` where `N` equals the `day` argument. If N < 10, it the input
-/// file name should have a leading 0 ie `day_01`.
-pub fn get_input_for_day(day: u8) -> std::io::Result<impl BufRead> {
+/// Without a profile this is the top-level `inputs/` directory; a named profile
+/// lives in its own subdirectory ie `inputs/<profile>/`.
+pub fn inputs_dir(profile: Option<&str>) -> PathBuf {
     let root_dir = std::env!("CARGO_MANIFEST_DIR");
-    let mut input_path = PathBuf::new();
-    input_path.push(root_dir);
-    input_path.push(INPUTS_DIR_NAME);
+    let mut dir = PathBuf::new();
+    dir.push(root_dir);
+    dir.push(INPUTS_DIR_NAME);
+    if let Some(profile) = profile {
+        dir.push(profile);
+    }
+    dir
+}
+
+/// List the names of every named profile, ie each subdirectory of `inputs/`.
+pub fn list_profiles() -> std::io::Result<Vec<String>> {
+    let mut profiles = vec![];
+    for entry in std::fs::read_dir(inputs_dir(None))? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            profiles.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// Get the path of the input file for the provided day and profile.
+///
+/// If there is no plain `day_N.txt` file but there is a compressed one ie `day_N.txt.gz`,
+/// the path to the compressed file is returned instead.
+pub fn input_path_for_day(day: u8, profile: Option<&str>) -> PathBuf {
+    let mut input_path = inputs_dir(profile);
     input_path.push(format!("{INPUT_FILE_NAME_PREFIX}{day:02}"));
     input_path.set_extension(INPUT_FILE_EXTENSION);
 
-    File::open(input_path).map(BufReader::new)
+    if !input_path.exists() {
+        let compressed = COMPRESSED_EXTENSIONS.iter().map(|ext| {
+            let mut path = input_path.clone().into_os_string();
+            path.push(format!(".{ext}"));
+            PathBuf::from(path)
+        });
+        if let Some(path) = compressed.into_iter().find(|path| path.exists()) {
+            return path;
+        }
+    }
+
+    input_path
+}
+
+/// Get the input data for the provided day, in the form of a [`BufRead`].
+///
+/// This data must already exist on disk in the `inputs/` directory (or
+/// `inputs/<profile>/` when a profile is given), named as `day_N` where `N`
+/// equals the `day` argument. If N < 10, it the input file name should have
+/// a leading 0 ie `day_01`. The file may be gzip or zstd compressed, see
+/// [`open_input`].
+pub fn get_input_for_day(day: u8, profile: Option<&str>) -> std::io::Result<Box<dyn BufRead>> {
+    open_input(input_path_for_day(day, profile))
+}
+
+/// Get the input for a command, which is read from `input_path` if one was given on the
+/// command line and otherwise the profile's input for the `day`.
+pub fn get_input(
+    day: u8,
+    profile: Option<&str>,
+    input_path: Option<&Path>,
+) -> std::io::Result<Box<dyn BufRead>> {
+    match input_path {
+        Some(path) => open_input(path),
+        None => get_input_for_day(day, profile),
+    }
+}
+
+/// Get the path of the input for a command, matching [`get_input`].
+pub fn get_input_path(day: u8, profile: Option<&str>, input_path: Option<&Path>) -> PathBuf {
+    match input_path {
+        Some(path) => path.to_path_buf(),
+        None => input_path_for_day(day, profile),
+    }
+}
+
+/// Open an input file as a [`BufRead`], decompressing it on the fly if need be.
+///
+/// Gzip and zstd compressed files are detected by either their `.gz`/`.zst`
+/// extension or the magic bytes at the start of the file.
+pub fn open_input(path: impl AsRef<Path>) -> std::io::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    let magic = reader.fill_buf()?;
+    let extension = path.extension().and_then(|ext| ext.to_str());
+
+    Ok(
+        if extension == Some("gz") || magic.starts_with(GZIP_MAGIC) {
+            Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+        } else if extension == Some("zst") || magic.starts_with(ZSTD_MAGIC) {
+            Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?))
+        } else {
+            Box::new(reader)
+        },
+    )
 }
 
 /// Obtain the [`Puzzle`] for the given `day` 1-12.
@@ -50,3 +155,179 @@ pub fn select_puzzle(day: u8) -> anyhow::Result<Puzzle> {
         _ => anyhow::bail!("Day {day} is not yet completed!"),
     })
 }
+
+/// Run a single `part` of the given `day`'s puzzle against `input`, with its default
+/// implementation.
+///
+/// The input is first normalized according to the day's [`Puzzle::normalization`] and the parse
+/// `mode`. Any [`ParseError`](crate::parse::ParseError) refers to the line of the original input.
+pub fn solve(
+    day: u8,
+    part: u8,
+    input: Box<dyn BufRead>,
+    mode: ParseMode,
+) -> anyhow::Result<Answer> {
+    solve_with(day, part, DEFAULT_IMPL, input, mode)
+}
+
+/// Like [`solve`], but with the implementation of the part that has the given `name`.
+pub fn solve_with(
+    day: u8,
+    part: u8,
+    name: &str,
+    input: Box<dyn BufRead>,
+    mode: ParseMode,
+) -> anyhow::Result<Answer> {
+    solve_impl(day, part, name, input, mode, false)
+}
+
+/// Like [`solve_with`], but without warning about the changes made to the input, ie because
+/// they were already reported when another implementation solved the same input.
+pub fn solve_quietly(
+    day: u8,
+    part: u8,
+    name: &str,
+    input: Box<dyn BufRead>,
+    mode: ParseMode,
+) -> anyhow::Result<Answer> {
+    solve_impl(day, part, name, input, mode, true)
+}
+
+fn solve_impl(
+    day: u8,
+    part: u8,
+    name: &str,
+    input: Box<dyn BufRead>,
+    mode: ParseMode,
+    quiet: bool,
+) -> anyhow::Result<Answer> {
+    let puzzle = select_puzzle(day)?;
+    let names = puzzle.implementations(part);
+    let normalization = puzzle.normalization;
+    let solver = puzzle.take_solver(part, name).with_context(|| {
+        format!(
+            "Day {day}, Part {part} has no implementation named {name:?}, expected one of: {}",
+            names.join(", ")
+        )
+    })?;
+
+    let mut input = NormalizedInput::new(input, normalization, mode);
+    if quiet {
+        input = input.quiet();
+    }
+    let line_map = input.line_map();
+    solver(Box::new(input)).map_err(|e| parse::remap_lines(e, &line_map))
+}
+
+/// Find what's worth a warning in the `input` of the given `day` before it's solved, see
+/// [`Puzzle::lint`].
+///
+/// The input is normalized like it is for the solvers, but without warning about the changes a
+/// second time. An input which can't be read or parsed has nothing to warn about here, since
+/// solving it reports why.
+pub fn lint(day: u8, input: Box<dyn BufRead>, mode: ParseMode) -> anyhow::Result<Vec<String>> {
+    let puzzle = select_puzzle(day)?;
+    let Some(lint) = puzzle.lint else {
+        return Ok(vec![]);
+    };
+    let input = NormalizedInput::new(input, puzzle.normalization, mode).quiet();
+    Ok(lint(Box::new(input)).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::AnswerStore;
+
+    /// Every day which [`select_puzzle`] knows about
+    const DAYS: std::ops::RangeInclusive<u8> = 1..=7;
+
+    /// Check both parts of every day against the real inputs and answers recorded under `inputs/`.
+    ///
+    /// Inputs aren't checked in, so anything which is missing is skipped rather than failed.
+    #[test]
+    fn test_golden_answers() {
+        if !inputs_dir(None).is_dir() {
+            eprintln!("Skipping golden answers: no {INPUTS_DIR_NAME}/ directory");
+            return;
+        }
+        let profiles = std::iter::once(None).chain(list_profiles().unwrap().into_iter().map(Some));
+
+        let mut checked = 0;
+        let mut failures = vec![];
+        for profile in profiles {
+            let profile = profile.as_deref();
+            let profile_name = profile.unwrap_or("default profile");
+            let store = AnswerStore::load(profile).unwrap();
+
+            for day in DAYS {
+                let path = input_path_for_day(day, profile);
+                if !path.exists() {
+                    eprintln!(
+                        "Skipping day {day} ({profile_name}): no input at {}",
+                        path.display()
+                    );
+                    continue;
+                }
+                for part in 1..=2 {
+                    let Some(expected) = store.get(day, part) else {
+                        eprintln!(
+                            "Skipping day {day} part {part} ({profile_name}): no recorded answer"
+                        );
+                        continue;
+                    };
+                    let input = get_input_for_day(day, profile).unwrap();
+                    let actual = match solve(day, part, input, ParseMode::Strict) {
+                        Ok(ans) => ans.to_string(),
+                        Err(e) => format!("error: {e:#}"),
+                    };
+                    checked += 1;
+                    if actual != expected {
+                        failures.push(format!(
+                            "day {day} part {part} ({profile_name}): expected {expected}, got {actual}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        eprintln!("Checked {checked} golden answer(s)");
+        assert!(
+            failures.is_empty(),
+            "Golden answers differ:\n{}",
+            failures.join("\n")
+        );
+    }
+
+    /// Every implementation of each part should agree with the default one on a generated input.
+    #[test]
+    fn test_implementations_agree() {
+        for day in DAYS {
+            let mut input = vec![];
+            let generate = select_puzzle(day).unwrap().generate;
+            generate(&mut crate::rng::Rng::new(u64::from(day)), 8, &mut input).unwrap();
+
+            for part in 1..=2 {
+                let answers: Vec<String> = select_puzzle(day)
+                    .unwrap()
+                    .implementations(part)
+                    .into_iter()
+                    .map(|name| {
+                        let input = Box::new(std::io::Cursor::new(input.clone()));
+                        match solve_with(day, part, name, input, ParseMode::Strict) {
+                            Ok(ans) => ans.to_string(),
+                            Err(e) => format!("error: {e:#}"),
+                        }
+                    })
+                    .collect();
+                assert!(
+                    answers.windows(2).all(|pair| pair[0] == pair[1]),
+                    "Day {day}, Part {part} implementations disagree: {answers:?}"
+                );
+            }
+        }
+
+        let input = Box::new(std::io::Cursor::new(vec![]));
+        assert!(solve_with(1, 1, "missing", input, ParseMode::Strict).is_err());
+    }
+}
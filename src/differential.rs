@@ -0,0 +1,47 @@
+//! Randomized differential testing of the solvers against naive reference implementations.
+
+use std::{fmt::Debug, ops::Range};
+
+use crate::rng::Rng;
+
+/// Run `cases` randomized cases, each with an input from `generate` seeded by its own seed, and
+/// panic on the first one where the `fast` and `reference` answers disagree.
+///
+/// The panic message holds the failing seed and input, so that the case can be reproduced with
+/// `DIFFERENTIAL_SEED=<seed> DIFFERENTIAL_CASES=1`.
+pub fn run<T: PartialEq + Debug>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> String,
+    fast: impl Fn(&str) -> T,
+    reference: impl Fn(&str) -> T,
+) {
+    for seed in seeds("DIFFERENTIAL", cases) {
+        let input = generate(&mut Rng::new(seed));
+        let fast_ans = fast(&input);
+        let reference_ans = reference(&input);
+        assert!(
+            fast_ans == reference_ans,
+            "Answers differ for seed {seed}: fast {fast_ans:?}, reference {reference_ans:?}\n\
+             Input:\n{input}"
+        );
+    }
+}
+
+/// Seeds of the cases for a seeded test loop to run, which are `cases` of them from 0.
+///
+/// The environment variables `<PREFIX>_SEED` and `<PREFIX>_CASES` override the seed of the first
+/// case and the number of cases, ie to reproduce a failure on its own.
+pub fn seeds(prefix: &str, cases: u64) -> Range<u64> {
+    let first_seed = env_or(&format!("{prefix}_SEED"), 0);
+    first_seed..first_seed + env_or(&format!("{prefix}_CASES"), cases)
+}
+
+/// Read a `u64` from the environment variable `var`, or `default` if it isn't set.
+fn env_or(var: &str, default: u64) -> u64 {
+    match std::env::var(var) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{var} should be a u64, not {value:?}")),
+        Err(_) => default,
+    }
+}
@@ -0,0 +1,126 @@
+//! Deterministic fuzzing of every day's parsers and solvers, which runs as part of `cargo test`.
+
+use std::{
+    io::Cursor,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    commands::minimize::panic_message,
+    days, differential,
+    normalize::NormalizedInput,
+    parse::ParseMode,
+    rng::Rng,
+    trace::{TraceFormat, TraceWriter},
+};
+
+/// Bytes which are inserted into inputs, favouring those that mean something to some day.
+const INTERESTING_BYTES: &[u8] = b"0123456789LR-,@.^S+* \t\r\n#x";
+/// Tokens which are inserted into inputs, to push the parsers towards their limits.
+const INTERESTING_TOKENS: &[&[u8]] = &[
+    b"18446744073709551615",
+    b"18446744073709551616",
+    b"340282366920938463463374607431768211455",
+    b"0",
+    b"-",
+    b"\n\n",
+    // A non-ASCII character, a byte order mark and invalid UTF-8
+    b"\xc3\xa9",
+    b"\xef\xbb\xbf",
+    b"\xff",
+];
+
+/// Feed `data` through everything that reads the input of `day`, in both parse modes.
+///
+/// Any of them may return an error, but none of them should panic, which includes arithmetic
+/// overflow and indexing out of bounds in a test build.
+pub fn fuzz_one(day: u8, data: &[u8]) {
+    for mode in [ParseMode::Strict, ParseMode::Lenient] {
+        for part in 1..=2 {
+            let input = Box::new(Cursor::new(data.to_vec()));
+            let _ = days::solve(day, part, input, mode);
+        }
+
+        let puzzle = days::select_puzzle(day).unwrap();
+        let input = NormalizedInput::new(Cursor::new(data.to_vec()), puzzle.normalization, mode);
+        let _ = (puzzle.validate)(Box::new(input));
+        let input = NormalizedInput::new(Cursor::new(data.to_vec()), puzzle.normalization, mode);
+        let _ = (puzzle.inspect)(Box::new(input));
+        if let Some(trace) = puzzle.trace {
+            let input =
+                NormalizedInput::new(Cursor::new(data.to_vec()), puzzle.normalization, mode);
+            let mut sink = std::io::sink();
+            let mut writer = TraceWriter::new(&mut sink, TraceFormat::Json, input.line_map());
+            let _ = trace(Box::new(input), &mut writer);
+        }
+    }
+}
+
+/// Run `cases` fuzz cases against `day`, each with an input generated from its own seed, and
+/// panic on the first one that panics with the seed and input needed to reproduce it.
+///
+/// Each input is either a small generated input for the day with a few random mutations, or
+/// entirely random bytes. A case can be rerun on its own with `FUZZ_SEED=<seed> FUZZ_CASES=1`.
+pub fn run(day: u8, cases: u64) {
+    for seed in differential::seeds("FUZZ", cases) {
+        let data = fuzz_input(day, &mut Rng::new(seed));
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| fuzz_one(day, &data))) {
+            panic!(
+                "Day {day} panicked for seed {seed}: {}\nInput:\n{}",
+                panic_message(payload.as_ref()),
+                String::from_utf8_lossy(&data)
+            );
+        }
+    }
+}
+
+fn fuzz_input(day: u8, rng: &mut Rng) -> Vec<u8> {
+    if rng.chance(0.1) {
+        let len = rng.range(0..=64);
+        return (0..len).map(|_| rng.next_u64() as u8).collect();
+    }
+
+    let mut data = vec![];
+    let generate = days::select_puzzle(day).unwrap().generate;
+    let size = rng.range(1..=6);
+    generate(rng, size, &mut data).unwrap();
+    for _ in 0..rng.range(0..=4) {
+        mutate(rng, &mut data);
+    }
+    data
+}
+
+/// Apply a single random mutation to `data`.
+fn mutate(rng: &mut Rng, data: &mut Vec<u8>) {
+    let pos = rng.range(0..=data.len());
+    match rng.range(0..=4) {
+        0 if pos < data.len() => {
+            data[pos] = INTERESTING_BYTES[rng.range(0..=INTERESTING_BYTES.len() - 1)];
+        }
+        1 => data.insert(
+            pos,
+            INTERESTING_BYTES[rng.range(0..=INTERESTING_BYTES.len() - 1)],
+        ),
+        2 => {
+            let end = rng.range(pos..=data.len().min(pos + 8));
+            data.drain(pos..end);
+        }
+        3 => {
+            let token = INTERESTING_TOKENS[rng.range(0..=INTERESTING_TOKENS.len() - 1)];
+            data.splice(pos..pos, token.iter().copied());
+        }
+        _ => {
+            // Replace a number with one of the tokens, ie to make it overflow
+            let start = data[..pos]
+                .iter()
+                .rposition(|b| !b.is_ascii_digit())
+                .map_or(0, |idx| idx + 1);
+            let end = data[pos..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .map_or(data.len(), |idx| pos + idx);
+            let token = INTERESTING_TOKENS[rng.range(0..=2)];
+            data.splice(start..end, token.iter().copied());
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt::Display;
+
+/// A single named statistic about an input, ie its number of lines.
+pub struct Stat {
+    pub name: String,
+    pub value: String,
+}
+
+impl Stat {
+    pub fn new(name: impl Into<String>, value: impl Display) -> Self {
+        Self {
+            name: name.into(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Summarize the distribution of some `values` as their min, median, mean and max. Any unsigned
+/// integers up to `u128` can be summarized, ie puzzle values as well as counts.
+pub fn summarize<T>(values: impl IntoIterator<Item = T>) -> String
+where
+    T: Copy + Ord + Display,
+    u128: TryFrom<T>,
+{
+    let mut values: Vec<T> = values.into_iter().collect();
+    if values.is_empty() {
+        return "n/a".to_string();
+    }
+    values.sort_unstable();
+
+    let mean = values
+        .iter()
+        .map(|v| u128::try_from(*v).map_or(f64::NAN, |v| v as f64))
+        .sum::<f64>()
+        / values.len() as f64;
+    format!(
+        "min {}, median {}, mean {mean:.2}, max {}",
+        values[0],
+        values[values.len() / 2],
+        values[values.len() - 1]
+    )
+}
+
+/// Format `part` as a percentage of `whole`.
+pub fn percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return "n/a".to_string();
+    }
+    format!("{:.2}%", part as f64 / whole as f64 * 100.0)
+}
@@ -1,25 +1,123 @@
+mod answers;
 mod cli;
+mod commands;
 mod days;
+#[cfg(test)]
+mod differential;
+#[cfg(test)]
+mod fuzz;
+mod inspect;
+mod normalize;
+mod parse;
 mod puzzle;
+mod rng;
+mod trace;
+mod validate;
 
-use cli::Cli;
-
-use clap::Parser;
+use cli::{Cli, Command};
+use parse::ParseMode;
 
 fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
-    let Cli { day, part } = cli;
-
-    let input = Box::new(days::get_input_for_day(day)?);
-
-    let puzzle = days::select_puzzle(day)?;
-    let ans = match part {
-        1 => (puzzle.part_one)(input),
-        2 => (puzzle.part_two)(input),
-        _ => unreachable!("There should only be two parts to each puzzle"),
-    }?;
-
-    println!("Answer for Day {day}, Part {part}: {ans}");
+    let cli = Cli::parse_args();
+    let Cli {
+        command,
+        run,
+        profile,
+        input,
+        strict: _,
+        lenient,
+    } = cli;
+    let mode = if lenient {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
 
-    Ok(())
+    match command {
+        Some(Command::Lock { wheels }) => {
+            commands::lock::run(&wheels, profile.as_deref(), input.as_deref(), mode)
+        }
+        Some(Command::Minimize {
+            day,
+            part,
+            implementation,
+            against,
+            output,
+        }) => commands::minimize::run(
+            &commands::minimize::Subject {
+                day,
+                part,
+                implementation: &implementation,
+                against: against.as_deref(),
+            },
+            profile.as_deref(),
+            input.as_deref(),
+            mode,
+            output.as_deref(),
+        ),
+        Some(Command::Online { interval }) => {
+            commands::online::run(profile.as_deref(), input.as_deref(), mode, interval)
+        }
+        Some(Command::Profiles { day }) => commands::profiles::run(day, mode),
+        Some(Command::Crosscheck { day, part }) => {
+            commands::crosscheck::run(day, part, profile.as_deref(), input.as_deref(), mode)
+        }
+        Some(Command::Generate {
+            day,
+            size,
+            seed,
+            output,
+        }) => commands::generate::run(day, size, seed, output.as_deref()),
+        Some(Command::Ids {
+            part,
+            radix,
+            counting,
+            report,
+            threads,
+            serial,
+        }) => commands::ids::run(
+            part,
+            days::IdOptions {
+                radix,
+                counting,
+                threads: match (serial, threads) {
+                    (true, _) => 1,
+                    (false, Some(threads)) => threads as usize,
+                    (false, None) => days::IdOptions::default().threads,
+                },
+            },
+            report,
+            profile.as_deref(),
+            input.as_deref(),
+            mode,
+        ),
+        Some(Command::Inspect { day }) => {
+            commands::inspect::run(day, profile.as_deref(), input.as_deref(), mode)
+        }
+        Some(Command::Scaling {
+            day,
+            part,
+            min_size,
+            steps,
+            factor,
+            seed,
+            repeats,
+        }) => commands::scaling::run(day, part, min_size, steps, factor, seed, repeats),
+        Some(Command::Trace {
+            day,
+            format,
+            output,
+        }) => commands::trace::run(
+            day,
+            profile.as_deref(),
+            input.as_deref(),
+            mode,
+            format,
+            output.as_deref(),
+        ),
+        Some(Command::Validate { day }) => {
+            commands::validate::run(day, profile.as_deref(), input.as_deref(), mode)
+        }
+        None => commands::run::run(run, profile.as_deref(), input.as_deref(), mode),
+    }
 }
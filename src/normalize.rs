@@ -0,0 +1,416 @@
+use std::{
+    cell::RefCell,
+    io::{BufRead, Read},
+    rc::Rc,
+};
+
+use crate::parse::ParseMode;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const COMMENT_PREFIX: u8 = b'#';
+
+/// How each day wants its input cleaned up before it reaches the solvers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normalization {
+    /// Remove the `\r` from `\r\n` line endings
+    pub strip_cr: bool,
+    /// Remove a UTF-8 byte order mark from the start of the input
+    pub strip_bom: bool,
+    /// What to do with spaces and tabs at the end of each line
+    pub trailing_whitespace: TrailingWhitespace,
+    /// Make sure that the last line of the input ends with `\n`
+    pub final_newline: bool,
+    /// Blank lines are meaningful in the input, so they're kept even in lenient mode
+    pub keep_blank_lines: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrailingWhitespace {
+    /// Leave trailing whitespace alone, for inputs where column alignment is significant
+    Preserve,
+    /// Trim trailing whitespace, and leading whitespace too in lenient mode, since the columns
+    /// don't matter
+    Trim,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            strip_cr: true,
+            strip_bom: true,
+            trailing_whitespace: TrailingWhitespace::Trim,
+            final_newline: true,
+            keep_blank_lines: false,
+        }
+    }
+}
+
+/// Tally of a single kind of change made to the input, for the diagnostics.
+#[derive(Default)]
+struct ChangeCount {
+    lines: usize,
+    first_line: usize,
+}
+
+impl ChangeCount {
+    fn add(&mut self, line: usize) {
+        if self.lines == 0 {
+            self.first_line = line;
+        }
+        self.lines += 1;
+    }
+}
+
+/// Maps line numbers of the normalized input back to those of the original input, which
+/// differ once lines have been skipped in lenient mode.
+#[derive(Clone, Default)]
+pub struct LineMap {
+    skipped: Rc<RefCell<Skipped>>,
+}
+
+/// What's kept about the lines skipped so far.
+enum Skipped {
+    /// Original line numbers of every skipped line, in ascending order
+    Lines(Vec<usize>),
+    /// Only how many lines were skipped, which is enough to map the line most recently read
+    Count(usize),
+}
+
+impl Default for Skipped {
+    fn default() -> Self {
+        Self::Lines(vec![])
+    }
+}
+
+impl LineMap {
+    pub fn is_empty(&self) -> bool {
+        match &*self.skipped.borrow() {
+            Skipped::Lines(lines) => lines.is_empty(),
+            Skipped::Count(count) => *count == 0,
+        }
+    }
+
+    /// Get the original line number of the normalized input's `line`.
+    ///
+    /// For input read with [`NormalizedInput::streaming`], this is only accurate for the line
+    /// most recently read.
+    pub fn original_line(&self, line: usize) -> usize {
+        let skipped = self.skipped.borrow();
+        let lines = match &*skipped {
+            Skipped::Lines(lines) => lines,
+            Skipped::Count(count) => return line + count,
+        };
+        let mut original = line;
+        for skipped in lines {
+            if *skipped <= original {
+                original += 1;
+            } else {
+                break;
+            }
+        }
+        original
+    }
+
+    fn push(&self, line: usize) {
+        match &mut *self.skipped.borrow_mut() {
+            Skipped::Lines(lines) => lines.push(line),
+            Skipped::Count(count) => *count += 1,
+        }
+    }
+}
+
+/// A [`BufRead`] adapter which applies a [`Normalization`] to the input one line at a time.
+///
+/// In [`ParseMode::Lenient`], blank lines (unless the day keeps them) and `#` comment lines are
+/// skipped as well. Once the input is exhausted (or the reader is dropped), a warning is printed
+/// for every kind of change that was made to it.
+pub struct NormalizedInput<R> {
+    inner: R,
+    config: Normalization,
+    mode: ParseMode,
+    line: Vec<u8>,
+    pos: usize,
+    line_no: usize,
+    stripped_bom: bool,
+    stripped_cr: ChangeCount,
+    trimmed: ChangeCount,
+    trimmed_leading: ChangeCount,
+    added_newline: bool,
+    skipped_blank: ChangeCount,
+    skipped_comment: ChangeCount,
+    line_map: LineMap,
+    reported: bool,
+}
+
+impl<R: BufRead> NormalizedInput<R> {
+    pub fn new(inner: R, config: Normalization, mode: ParseMode) -> Self {
+        Self {
+            inner,
+            config,
+            mode,
+            line: vec![],
+            pos: 0,
+            line_no: 0,
+            stripped_bom: false,
+            stripped_cr: ChangeCount::default(),
+            trimmed: ChangeCount::default(),
+            trimmed_leading: ChangeCount::default(),
+            added_newline: false,
+            skipped_blank: ChangeCount::default(),
+            skipped_comment: ChangeCount::default(),
+            line_map: LineMap::default(),
+            reported: false,
+        }
+    }
+
+    /// Don't warn about the changes made to the input, ie because another pass over the same
+    /// input reports them.
+    pub fn quiet(mut self) -> Self {
+        self.reported = true;
+        self
+    }
+
+    /// Only count the lines skipped in lenient mode rather than remembering each of them, so that
+    /// the memory used stays constant for inputs that never end. Errors can then only be mapped
+    /// back to the original input for the line most recently read.
+    pub fn streaming(self) -> Self {
+        *self.line_map.skipped.borrow_mut() = Skipped::Count(0);
+        self
+    }
+
+    /// Get a handle on the mapping of line numbers back to the original input, which keeps
+    /// updating as the input is read.
+    pub fn line_map(&self) -> LineMap {
+        self.line_map.clone()
+    }
+
+    /// Read and normalize the next line into the buffer, returning `false` at the end of input.
+    fn next_line(&mut self) -> std::io::Result<bool> {
+        loop {
+            self.line.clear();
+            self.pos = 0;
+            if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(false);
+            }
+            self.line_no += 1;
+            self.normalize_line();
+
+            if self.mode == ParseMode::Lenient {
+                self.trim_leading();
+                let content = self.line.trim_ascii();
+                let skipped = if content.is_empty() && !self.config.keep_blank_lines {
+                    &mut self.skipped_blank
+                } else if content.first() == Some(&COMMENT_PREFIX) {
+                    &mut self.skipped_comment
+                } else {
+                    return Ok(true);
+                };
+                skipped.add(self.line_no);
+                self.line_map.push(self.line_no);
+                continue;
+            }
+
+            return Ok(true);
+        }
+    }
+
+    /// Trim stray whitespace from the start of the line in lenient mode, unless the day's
+    /// columns are significant.
+    fn trim_leading(&mut self) {
+        if self.config.trailing_whitespace == TrailingWhitespace::Preserve {
+            return;
+        }
+        let leading = self
+            .line
+            .iter()
+            .take_while(|b| matches!(b, b' ' | b'\t'))
+            .count();
+        if leading > 0 {
+            self.line.drain(..leading);
+            self.trimmed_leading.add(self.line_no);
+        }
+    }
+
+    /// Apply the normalization to the line that was just read into the buffer.
+    fn normalize_line(&mut self) {
+        if self.line_no == 1 && self.config.strip_bom && self.line.starts_with(UTF8_BOM) {
+            self.line.drain(..UTF8_BOM.len());
+            self.stripped_bom = true;
+        }
+
+        let had_newline = self.line.last() == Some(&b'\n');
+        if had_newline {
+            self.line.pop();
+        }
+        if self.config.strip_cr && self.line.last() == Some(&b'\r') {
+            self.line.pop();
+            self.stripped_cr.add(self.line_no);
+        }
+        if self.config.trailing_whitespace == TrailingWhitespace::Trim
+            && self.line.last().is_some_and(|b| matches!(b, b' ' | b'\t'))
+        {
+            while self.line.last().is_some_and(|b| matches!(b, b' ' | b'\t')) {
+                self.line.pop();
+            }
+            self.trimmed.add(self.line_no);
+        }
+        if had_newline {
+            self.line.push(b'\n');
+        } else if self.config.final_newline {
+            self.line.push(b'\n');
+            self.added_newline = true;
+        }
+    }
+}
+
+impl<R> NormalizedInput<R> {
+    /// Print a warning for each kind of change that has been made to the input.
+    fn report(&mut self) {
+        if self.reported {
+            return;
+        }
+        self.reported = true;
+
+        if self.stripped_bom {
+            eprintln!("Warning: stripped UTF-8 byte order mark from input");
+        }
+        for (count, what, kind) in [
+            (&self.stripped_cr, "stripped carriage return from", ""),
+            (&self.trimmed, "trimmed trailing whitespace from", ""),
+            (&self.trimmed_leading, "trimmed leading whitespace from", ""),
+            (&self.skipped_blank, "skipped", "blank "),
+            (&self.skipped_comment, "skipped", "comment "),
+        ] {
+            if count.lines > 0 {
+                eprintln!(
+                    "Warning: {what} {} {kind}input line(s), starting at line {}",
+                    count.lines, count.first_line
+                );
+            }
+        }
+        if self.added_newline {
+            eprintln!(
+                "Warning: added missing final newline to input line {}",
+                self.line_no
+            );
+        }
+    }
+}
+
+impl<R: BufRead> Read for NormalizedInput<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for NormalizedInput<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        // Loop, since a line that only held a byte order mark normalizes down to nothing
+        while self.pos >= self.line.len() {
+            if !self.next_line()? {
+                self.report();
+                break;
+            }
+        }
+        Ok(&self.line[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.line.len());
+    }
+}
+
+impl<R> Drop for NormalizedInput<R> {
+    fn drop(&mut self) {
+        // Solvers don't always read their input through to the end
+        self.report();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(raw: &str, config: Normalization) -> String {
+        let mut out = String::new();
+        NormalizedInput::new(raw.as_bytes(), config, ParseMode::Strict)
+            .read_to_string(&mut out)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_default_normalization() {
+        let raw = "\u{FEFF}L68  \r\nR5\r\nL1";
+        assert_eq!("L68\nR5\nL1\n", normalize(raw, Normalization::default()));
+    }
+
+    #[test]
+    fn test_preserve_trailing_whitespace() {
+        let config = Normalization {
+            trailing_whitespace: TrailingWhitespace::Preserve,
+            ..Default::default()
+        };
+        let raw = "123 328 \r\n*   +  ";
+        assert_eq!("123 328 \n*   +  \n", normalize(raw, config));
+    }
+
+    #[test]
+    fn test_lenient_skips_lines() {
+        let raw = "# comment\nL68\n\n  # indented\nR5";
+        let mut input =
+            NormalizedInput::new(raw.as_bytes(), Normalization::default(), ParseMode::Lenient);
+        let line_map = input.line_map();
+        let mut out = String::new();
+        input.read_to_string(&mut out).unwrap();
+
+        assert_eq!("L68\nR5\n", out);
+        assert_eq!(2, line_map.original_line(1));
+        assert_eq!(5, line_map.original_line(2));
+    }
+
+    #[test]
+    fn test_streaming_counts_skipped_lines() {
+        let raw = "# comment\nL68\n\n  # indented\nR5";
+        let mut input =
+            NormalizedInput::new(raw.as_bytes(), Normalization::default(), ParseMode::Lenient)
+                .streaming();
+        let line_map = input.line_map();
+        let mut line = String::new();
+
+        input.read_line(&mut line).unwrap();
+        assert_eq!("L68\n", line);
+        assert_eq!(2, line_map.original_line(1));
+
+        line.clear();
+        input.read_line(&mut line).unwrap();
+        assert_eq!("R5\n", line);
+        assert_eq!(5, line_map.original_line(2));
+    }
+
+    #[test]
+    fn test_lenient_trims_leading_whitespace() {
+        let lenient = |raw: &str, config| {
+            let mut out = String::new();
+            NormalizedInput::new(raw.as_bytes(), config, ParseMode::Lenient)
+                .read_to_string(&mut out)
+                .unwrap();
+            out
+        };
+        assert_eq!(
+            "L68\nR48\n",
+            lenient("L68\n  R48", Normalization::default())
+        );
+        assert_eq!("  R48\n", normalize("  R48", Normalization::default()));
+
+        let columns = Normalization {
+            trailing_whitespace: TrailingWhitespace::Preserve,
+            ..Default::default()
+        };
+        assert_eq!(" 12 \n  *\n", lenient(" 12 \n  *", columns));
+    }
+}
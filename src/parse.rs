@@ -0,0 +1,163 @@
+use std::{fmt::Display, ops::Range, path::Path};
+
+use crate::normalize::LineMap;
+
+/// How forgiving the parsing of puzzle inputs should be.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParseMode {
+    /// Anything unexpected in the input is an error
+    #[default]
+    Strict,
+    /// Blank lines and `#` comment lines are skipped and leading whitespace is trimmed, with a
+    /// warning for what was changed
+    Lenient,
+}
+
+/// An error from parsing a puzzle input, pointing at the offending text within its line.
+#[derive(Clone, Debug)]
+pub struct ParseError {
+    pub message: String,
+    /// 1-based line number
+    pub line: usize,
+    /// Contents of the offending line
+    pub source_line: String,
+    /// Byte span of the offending text within [`Self::source_line`]
+    pub span: Range<usize>,
+}
+
+impl ParseError {
+    /// Create an error for the text at `span` within the `source_line`.
+    ///
+    /// The span may run past the end of the line, for errors about something that's missing.
+    pub fn new(
+        message: impl Into<String>,
+        line: usize,
+        source_line: &str,
+        span: Range<usize>,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            line,
+            source_line: source_line.to_string(),
+            span,
+        }
+    }
+
+    /// Create an error for `text`, which must be a subslice of `source_line`.
+    pub fn at(message: impl Into<String>, line: usize, source_line: &str, text: &str) -> Self {
+        let start = text.as_ptr() as usize - source_line.as_ptr() as usize;
+        debug_assert!(
+            start + text.len() <= source_line.len(),
+            "text should be a subslice of source_line"
+        );
+        Self::new(message, line, source_line, start..start + text.len())
+    }
+
+    /// Create an error pointing just past the end of `source_line`, for something missing.
+    pub fn after(message: impl Into<String>, line: usize, source_line: &str) -> Self {
+        let end = source_line.len();
+        Self::new(message, line, source_line, end..end + 1)
+    }
+
+    /// The offending text, which is empty if the span is past the end of the line.
+    pub fn text(&self) -> &str {
+        let end = self.span.end.min(self.source_line.len());
+        self.source_line
+            .get(self.span.start..end)
+            .unwrap_or_default()
+    }
+
+    /// 1-based column of the start of the span, counted in characters.
+    pub fn column(&self) -> usize {
+        let start = self.span.start.min(self.source_line.len());
+        let prefix_len = self
+            .source_line
+            .get(..start)
+            .map_or(start, |prefix| prefix.chars().count());
+        prefix_len + 1 + (self.span.start - start)
+    }
+
+    /// Render the error compiler-style, with its source line and the span underlined.
+    pub fn render(&self, path: &Path) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let underline_len = self.text().chars().count().max(1);
+        format!(
+            "error: {}\n{gutter}--> {}:{}:{}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+            self.message,
+            path.display(),
+            self.line,
+            self.column(),
+            self.line,
+            self.source_line,
+            " ".repeat(self.column() - 1),
+            "^".repeat(underline_len),
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (line {}, column {}: {:?})",
+            self.message,
+            self.line,
+            self.column(),
+            self.text()
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// If `err` was caused by a [`ParseError`], print it compiler-style against the input at `path`,
+/// and replace it with a short summary. Any other error is returned as-is.
+pub fn report(err: anyhow::Error, path: &Path) -> anyhow::Error {
+    match err.chain().find_map(|e| e.downcast_ref::<ParseError>()) {
+        Some(parse_err) => {
+            eprintln!("{}", parse_err.render(path));
+            anyhow::anyhow!("Could not parse input {}", path.display())
+        }
+        None => err,
+    }
+}
+
+/// Point any [`ParseError`] behind `err` back at its line in the original input, rather than the
+/// normalized input that the solver saw.
+pub fn remap_lines(err: anyhow::Error, line_map: &LineMap) -> anyhow::Error {
+    if line_map.is_empty() {
+        return err;
+    }
+    let Some(parse_err) = err.chain().find_map(|e| e.downcast_ref::<ParseError>()) else {
+        return err;
+    };
+
+    let mut remapped = parse_err.clone();
+    remapped.line = line_map.original_line(parse_err.line);
+    if err.is::<ParseError>() {
+        anyhow::Error::new(remapped)
+    } else {
+        anyhow::Error::new(remapped).context(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let line = "L68\nR4x2".lines().nth(1).unwrap();
+        let err = ParseError::at("Could not parse valid magnitude int", 2, line, &line[1..]);
+        let expected = "error: Could not parse valid magnitude int
+ --> day_01.txt:2:2
+  |
+2 | R4x2
+  |  ^^^";
+        assert_eq!(expected, err.render(Path::new("day_01.txt")));
+
+        let err = ParseError::after("Missing operator", 10, "*  +");
+        assert_eq!(5, err.column());
+        assert!(err.render(Path::new("day_06.txt")).ends_with("   |     ^"));
+    }
+}
@@ -1,12 +1,80 @@
-use std::{fmt::Display, io::BufRead};
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+};
+
+use crate::{
+    inspect::Stat, normalize::Normalization, rng::Rng, trace::TraceWriter, validate::Violation,
+};
 
 /// At the highest level, a puzzle is a function that takes an input reader, and returns an [`Answer`].
 pub type Solver = Box<dyn FnOnce(Box<dyn BufRead>) -> anyhow::Result<Answer>>;
 /// At the highest level, the answer from each day is just displayed to the user.
 pub type Answer = Box<dyn Display>;
+/// Checks an input against the shape that a day expects, returning every place that it differs.
+pub type Validator = Box<dyn FnOnce(Box<dyn BufRead>) -> anyhow::Result<Vec<Violation>>>;
+/// Summarizes the parts of an input's shape that are specific to a day.
+pub type Inspector = Box<dyn FnOnce(Box<dyn BufRead>) -> anyhow::Result<Vec<Stat>>>;
+/// Writes a random, valid input of the given size, where what "size" means is up to each day.
+pub type Generator = Box<dyn Fn(&mut Rng, usize, &mut dyn Write) -> std::io::Result<()>>;
+/// Finds anything in an input which the solvers accept, but which may not be what was meant.
+pub type Linter = Box<dyn FnOnce(Box<dyn BufRead>) -> anyhow::Result<Vec<String>>>;
+/// Writes a record of every step that a day's solution takes through an input.
+pub type Tracer = Box<dyn FnOnce(Box<dyn BufRead>, &mut TraceWriter) -> anyhow::Result<()>>;
+
+/// Name of the implementations held in [`Puzzle::part_one`] and [`Puzzle::part_two`].
+pub const DEFAULT_IMPL: &str = "default";
 
 /// Simple wrapper representing the puzzle for a given day.
 pub struct Puzzle {
     pub part_one: Solver,
     pub part_two: Solver,
+    /// Other named implementations of either part, ie a brute force version which the default
+    /// one can be checked against
+    pub alternatives: Vec<Implementation>,
+    /// How the input should be cleaned up before it is handed to either part
+    pub normalization: Normalization,
+    /// Checks the (normalized) input without solving anything
+    pub validate: Validator,
+    /// Collects day-specific statistics about the (normalized) input
+    pub inspect: Inspector,
+    /// Generates synthetic inputs for stress testing and benchmarking
+    pub generate: Generator,
+    /// Finds what's worth a warning in the (normalized) input, for the days which have any, so
+    /// that it's reported once per command rather than by every solver that reads the input
+    pub lint: Option<Linter>,
+    /// Traces the steps that the solution takes, for the days which support it
+    pub trace: Option<Tracer>,
+}
+
+/// An alternative implementation of one part of a puzzle.
+pub struct Implementation {
+    pub name: &'static str,
+    pub part: u8,
+    pub solver: Solver,
+}
+
+impl Puzzle {
+    /// Names of every implementation of `part`, starting with [`DEFAULT_IMPL`].
+    pub fn implementations(&self, part: u8) -> Vec<&'static str> {
+        let alternatives = self
+            .alternatives
+            .iter()
+            .filter(|alternative| alternative.part == part)
+            .map(|alternative| alternative.name);
+        std::iter::once(DEFAULT_IMPL).chain(alternatives).collect()
+    }
+
+    /// Take the solver for `part` with the given `name`, if there is one.
+    pub fn take_solver(self, part: u8, name: &str) -> Option<Solver> {
+        match (part, name) {
+            (1, DEFAULT_IMPL) => Some(self.part_one),
+            (2, DEFAULT_IMPL) => Some(self.part_two),
+            _ => self
+                .alternatives
+                .into_iter()
+                .find(|alternative| alternative.part == part && alternative.name == name)
+                .map(|alternative| alternative.solver),
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use std::ops::RangeInclusive;
+
+/// Small seedable pseudo-random number generator ([SplitMix64](https://prng.di.unimi.it/splitmix64.c)).
+///
+/// This is kept in-crate rather than pulled in as a dependency, so that the same seed is
+/// guaranteed to produce the same input on every machine and version.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly pick a number from the inclusive `range`.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "range should not be empty");
+        let span = (end - start) as u128 + 1;
+        // Widening multiply maps the random number onto the span, with negligible bias
+        let offset = (self.next_u64() as u128 * span) >> 64;
+        start + offset as usize
+    }
+
+    /// Returns `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+
+use crate::normalize::LineMap;
+
+/// How a trace is written out.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum TraceFormat {
+    /// A header row of field names, then one row per record
+    Csv,
+    /// An array holding one object per record, each on its own line
+    Json,
+}
+
+/// The value of a single field of a trace record.
+pub enum Field {
+    Int(u128),
+    Bool(bool),
+    Text(String),
+}
+
+/// Writes a trace of the steps that a solver takes, one record per line of the input.
+///
+/// Every record starts with the `line` it came from, which is pointed back at the original
+/// input if it was normalized, followed by the day's own fields. Those should have the same
+/// names in every record, since the CSV header is taken from the first one.
+pub struct TraceWriter<'a> {
+    out: &'a mut dyn Write,
+    format: TraceFormat,
+    line_map: LineMap,
+    records: usize,
+}
+
+impl<'a> TraceWriter<'a> {
+    pub fn new(out: &'a mut dyn Write, format: TraceFormat, line_map: LineMap) -> Self {
+        Self {
+            out,
+            format,
+            line_map,
+            records: 0,
+        }
+    }
+
+    /// Write the record for line `line_no` of the (normalized) input.
+    pub fn record(&mut self, line_no: usize, fields: &[(&str, Field)]) -> std::io::Result<()> {
+        let line = Field::Int(self.line_map.original_line(line_no) as u128);
+        let fields = std::iter::once(("line", &line)).chain(fields.iter().map(|(k, v)| (*k, v)));
+
+        match self.format {
+            TraceFormat::Csv => {
+                let (names, values): (Vec<_>, Vec<_>) = fields.unzip();
+                if self.records == 0 {
+                    writeln!(self.out, "{}", names.join(","))?;
+                }
+                let values: Vec<String> = values.into_iter().map(csv_value).collect();
+                writeln!(self.out, "{}", values.join(","))?;
+            }
+            TraceFormat::Json => {
+                let fields: Vec<String> = fields
+                    .map(|(name, value)| format!("{}: {}", json_string(name), json_value(value)))
+                    .collect();
+                let sep = if self.records == 0 { "[" } else { "," };
+                write!(self.out, "{sep}\n  {{{}}}", fields.join(", "))?;
+            }
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Close off the trace, once every record has been written.
+    pub fn finish(self) -> std::io::Result<()> {
+        if self.format == TraceFormat::Json {
+            let open = if self.records == 0 { "[" } else { "" };
+            writeln!(self.out, "{open}\n]")?;
+        }
+        self.out.flush()
+    }
+}
+
+fn csv_value(value: &Field) -> String {
+    match value {
+        Field::Int(num) => num.to_string(),
+        Field::Bool(b) => b.to_string(),
+        Field::Text(text) if text.contains([',', '"', '\n']) => {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        }
+        Field::Text(text) => text.clone(),
+    }
+}
+
+fn json_value(value: &Field) -> String {
+    match value {
+        Field::Int(num) => num.to_string(),
+        Field::Bool(b) => b.to_string(),
+        Field::Text(text) => json_string(text),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
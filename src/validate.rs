@@ -0,0 +1,110 @@
+use std::{fmt::Display, str::FromStr};
+
+pub const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+/// A single place where an input doesn't match the shape that its day expects.
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Report every character in `line` which isn't one of the `allowed` characters.
+pub fn check_chars(line_no: usize, line: &str, allowed: &[char], violations: &mut Vec<Violation>) {
+    for (idx, c) in line.chars().enumerate() {
+        if !allowed.contains(&c) {
+            let allowed = allowed.iter().map(|c| format!("'{c}'")).collect::<Vec<_>>();
+            violations.push(Violation::new(
+                line_no,
+                idx + 1,
+                format!("unexpected {c:?}, expected one of {}", allowed.join(", ")),
+            ));
+        }
+    }
+}
+
+/// Check that `s`, found at `column` of the line, is an unsigned integer.
+///
+/// On success the parsed number is returned, otherwise a violation describing `what` was
+/// expected is recorded. The number is too large if it doesn't fit a `T`.
+pub fn check_unsigned<T: FromStr>(
+    line_no: usize,
+    column: usize,
+    s: &str,
+    what: &str,
+    violations: &mut Vec<Violation>,
+) -> Option<T> {
+    if s.is_empty() {
+        violations.push(Violation::new(line_no, column, format!("missing {what}")));
+        return None;
+    }
+    if let Some(idx) = s.find(|c: char| !c.is_ascii_digit()) {
+        let c = s[idx..].chars().next().unwrap_or_default();
+        violations.push(Violation::new(
+            line_no,
+            column + s[..idx].chars().count(),
+            format!("unexpected {c:?} in {what}, expected a digit"),
+        ));
+        return None;
+    }
+    match s.parse() {
+        Ok(num) => Some(num),
+        Err(_) => {
+            violations.push(Violation::new(
+                line_no,
+                column,
+                format!("{what} is too large"),
+            ));
+            None
+        }
+    }
+}
+
+/// Check that `s`, found at `column` of the line, is a range in the form `{start}-{end}` with
+/// `start <= end`, where both bounds fit a `T`.
+pub fn check_range<T: FromStr + PartialOrd + Display>(
+    line_no: usize,
+    column: usize,
+    s: &str,
+    violations: &mut Vec<Violation>,
+) {
+    let Some((start, end)) = s.split_once('-') else {
+        violations.push(Violation::new(
+            line_no,
+            column,
+            format!("malformed range {s:?}, expected {{start}}-{{end}}"),
+        ));
+        return;
+    };
+    let end_column = column + start.chars().count() + 1;
+    let start: Option<T> = check_unsigned(line_no, column, start, "range start", violations);
+    let end = check_unsigned(line_no, end_column, end, "range end", violations);
+    if let Some((start, end)) = start.zip(end)
+        && start > end
+    {
+        violations.push(Violation::new(
+            line_no,
+            column,
+            format!("range start {start} is greater than its end {end}"),
+        ));
+    }
+}